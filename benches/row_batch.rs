@@ -0,0 +1,72 @@
+//! Compares decoding by name with `RowBatch`, which resolves the column positions once.
+//!
+//! Needs a running database, e.g. `DATABASE_URL="host=localhost user=postgres" cargo bench --bench row_batch`,
+//! and is skipped without one. Each way is timed over several runs, keeping the fastest.
+
+use std::{hint::black_box, time::{Duration, Instant}};
+use tokio_postgres::NoTls;
+use tokio_postgres_utils::{BoxError, RowBatch, TryFromRow};
+
+#[derive(TryFromRow, PartialEq)]
+struct Wide {
+    c01: i32,
+    c02: i32,
+    c03: i32,
+    c04: i32,
+    c05: i32,
+    c06: i32,
+    c07: i32,
+    c08: i32,
+    c09: i32,
+    c10: i32,
+    c11: String,
+    c12: String,
+    c13: String,
+    c14: String,
+    c15: String,
+    c16: String,
+    c17: String,
+    c18: String,
+    c19: String,
+    c20: String,
+}
+
+const RUNS: usize = 10;
+
+/// The fastest of [`RUNS`] runs of `f`.
+fn best_of(mut f: impl FnMut() -> Result<Vec<Wide>, BoxError>) -> Result<Duration, BoxError> {
+    let mut best = Duration::MAX;
+    for _ in 0..RUNS {
+        let start = Instant::now();
+        black_box(f()?);
+        best = best.min(start.elapsed());
+    }
+    Ok(best)
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> Result<(), BoxError> {
+    let Ok(config) = std::env::var("DATABASE_URL") else {
+        println!("row_batch: skipped, `DATABASE_URL` is not set");
+        return Ok(());
+    };
+    let (client, connection) = tokio_postgres::connect(&config, NoTls).await?;
+    tokio::spawn(connection);
+
+    let columns = (1..=20)
+        .map(|i| if i <= 10 { format!("g AS c{i:02}") } else { format!("g::text AS c{i:02}") })
+        .collect::<Vec<_>>()
+        .join(", ");
+    let rows = client.query(&format!("SELECT {columns} FROM generate_series(1, 100000) g"), &[]).await?;
+
+    let naive = rows.iter().map(Wide::try_from).collect::<Result<Vec<_>, _>>()?;
+    let planned = RowBatch::<Wide>::new(&rows).collect::<Result<Vec<_>, _>>()?;
+    assert!(naive == planned);
+
+    let by_name = best_of(|| Ok(rows.iter().map(Wide::try_from).collect::<Result<Vec<_>, _>>()?))?;
+    let planned = best_of(|| RowBatch::<Wide>::new(&rows).collect())?;
+    println!("{} rows of {} columns, best of {RUNS} runs", rows.len(), rows[0].len());
+    println!("by name:  {by_name:?}");
+    println!("planned:  {planned:?}");
+    Ok(())
+}
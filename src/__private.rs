@@ -0,0 +1,506 @@
+//! Support code for the derive macros, not part of the public API.
+
+use crate::{BoxError, ColumnError, ColumnKey, ColumnPlan, CompositeRow, FromRow, MultiDecodeError, SortOrder};
+use std::{collections::HashMap, fmt, marker::PhantomData};
+use tokio_postgres::{
+    row::RowIndex,
+    types::{private::BytesMut, FromSql, FromSqlOwned, IsNull, ToSql, Type},
+    Column, Row,
+};
+
+/// Reads a `#[column(flatten)]` field from whatever the parent is read from.
+pub trait Flatten<T> {
+    fn flatten(&self) -> Result<T, BoxError>;
+}
+
+/// Double-quotes a column name for generated SQL, doubling the quotes inside it.
+pub fn quote_ident(ident: &str) -> String {
+    format!("\"{}\"", ident.replace('"', "\"\""))
+}
+
+/// The `COL_<FIELD>` key of a column read by `T`.
+pub const fn column_key<T: ?Sized>(name: &'static str) -> ColumnKey<T> {
+    ColumnKey { name, _marker: PhantomData }
+}
+
+/// Builds the `ORDER BY` clause of a struct's `order_by` function, empty without keys.
+pub fn order_by<T: ?Sized>(keys: &[(ColumnKey<T>, SortOrder)], quote: bool) -> String {
+    if keys.is_empty() {
+        return String::new();
+    }
+    let keys: Vec<String> = keys
+        .iter()
+        .map(|(key, order)| {
+            let column = if quote { quote_ident(key.name) } else { key.name.to_owned() };
+            format!("{column} {}", order.as_sql())
+        })
+        .collect();
+    format!("ORDER BY {}", keys.join(", "))
+}
+
+/// Fails const evaluation with the message of the first column in `read` that isn't `selected`, for `query_as!`.
+///
+/// Names are compared ignoring ASCII case, as `Row::get` falls back to.
+pub const fn check_select(read: &[(&str, &'static str)], selected: &[&str]) {
+    let mut i = 0;
+    while i < read.len() {
+        let (column, message) = read[i];
+        let mut j = 0;
+        while j < selected.len() && !selected[j].eq_ignore_ascii_case(column) {
+            j += 1;
+        }
+        if j == selected.len() {
+            panic!("{}", message);
+        }
+        i += 1;
+    }
+}
+
+/// The column a field of a `#[column(name_map)]` struct is read from, `column` if the map has none.
+pub fn mapped_column<'a>(map: &'a HashMap<&str, &'a str>, field: &str, column: &'a str) -> &'a str {
+    map.get(field).copied().unwrap_or(column)
+}
+
+/// Any column value, only read to tell whether it's `NULL`.
+struct AnyValue;
+
+impl<'a> FromSql<'a> for AnyValue {
+    fn from_sql(_: &Type, _: &'a [u8]) -> Result<Self, BoxError> {
+        Ok(AnyValue)
+    }
+
+    fn accepts(_: &Type) -> bool {
+        true
+    }
+}
+
+/// Whether a key column of `try_from_row_opt`, or a column of a flattened `Option`, is `NULL`.
+/// A missing column isn't, so reading the row reports it.
+pub fn is_null<I: RowIndex + fmt::Display>(r: &Row, column: I) -> bool {
+    matches!(r.try_get::<_, Option<AnyValue>>(column), Ok(None))
+}
+
+/// Decodes a `#[column(try_types(..))]` column as one of its types, named for the error.
+pub type Attempt<'a, T> = (&'a str, &'a dyn Fn() -> Result<T, BoxError>);
+
+/// Reads a `#[column(try_types(..))]` field with the first attempt that succeeds.
+pub fn try_types<T>(column: impl fmt::Display, attempts: &[Attempt<'_, T>]) -> Result<T, BoxError> {
+    let mut errors = Vec::new();
+    for (ty, attempt) in attempts {
+        match attempt() {
+            Ok(value) => return Ok(value),
+            Err(err) => errors.push(format!("as `{ty}`: {err}")),
+        }
+    }
+    Err(format!("no type of `try_types` could read column `{column}`, {}", errors.join(", ")).into())
+}
+
+/// Decodes a `#[column(retry = N)]` field up to `attempts` times, returning the last error if every attempt fails.
+pub fn retry<T, E>(attempts: usize, mut decode: impl FnMut() -> Result<T, E>) -> Result<T, E> {
+    let mut attempt = 1;
+    loop {
+        match decode() {
+            Err(_) if attempt < attempts => attempt += 1,
+            value => return value,
+        }
+    }
+}
+
+/// The write transform of the field at position `N` of a struct, generated by `Insert` for `#[column(write_with)]` fields.
+pub trait WriteWith<const N: usize> {
+    type Field;
+    fn to_sql_checked(value: &Self::Field, ty: &Type, out: &mut BytesMut) -> Result<IsNull, BoxError>;
+}
+
+/// A `#[column(write_with)]` field, bound as what its write transform returns.
+#[repr(transparent)]
+pub struct Written<S: WriteWith<N>, const N: usize>(S::Field, PhantomData<fn() -> S>);
+
+/// Borrows a field as its `Written` param.
+pub fn written<S: WriteWith<N>, const N: usize>(value: &S::Field) -> &Written<S, N> {
+    // SAFETY: `Written` is a transparent wrapper of `S::Field`, its `PhantomData` has no size nor alignment.
+    unsafe { &*(value as *const S::Field as *const Written<S, N>) }
+}
+
+/// Moves a field into its `Written` param.
+pub fn into_written<S: WriteWith<N>, const N: usize>(value: S::Field) -> Written<S, N> {
+    Written(value, PhantomData)
+}
+
+impl<S: WriteWith<N>, const N: usize> fmt::Debug for Written<S, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Written").field("field", &N).finish_non_exhaustive()
+    }
+}
+
+impl<S: WriteWith<N>, const N: usize> ToSql for Written<S, N> {
+    fn to_sql(&self, ty: &Type, out: &mut BytesMut) -> Result<IsNull, BoxError> {
+        S::to_sql_checked(&self.0, ty, out)
+    }
+
+    // The written type is checked by `to_sql_checked`.
+    fn accepts(_: &Type) -> bool {
+        true
+    }
+
+    fn to_sql_checked(&self, ty: &Type, out: &mut BytesMut) -> Result<IsNull, BoxError> {
+        S::to_sql_checked(&self.0, ty, out)
+    }
+}
+
+/// The position of the `nth` column named `name`, or one past the last column if there are fewer, which rows reject.
+pub fn nth_column(columns: &[Column], name: &str, nth: usize) -> usize {
+    let mut matches = columns.iter().enumerate().filter(|(_, c)| c.name() == name);
+    matches.nth(nth).map_or(columns.len(), |(index, _)| index)
+}
+
+/// Records the error of a field of a `#[column(collect_errors)]` struct.
+pub fn collect<T>(errors: &mut MultiDecodeError, column: &'static str, value: Result<T, BoxError>) -> Option<T> {
+    value.map_err(|err| errors.errors.push((column, err))).ok()
+}
+
+/// Names the column of a failed field when the `error-context` feature is on.
+pub fn with_context<T>(column: &'static str, value: Result<T, BoxError>) -> Result<T, ColumnError> {
+    value.map_err(|source| ColumnError { column, source })
+}
+
+/// Fails with the first column read by two fields of a `#[column(strict_flatten)]` struct,
+/// unless the later one is a `#[column(flatten, override)]` field.
+pub fn check_overlap(fields: &[(&'static str, &'static [&'static str], bool)]) -> Result<(), (&'static str, BoxError)> {
+    for (i, (field, columns, overrides)) in fields.iter().enumerate() {
+        if *overrides {
+            continue;
+        }
+        for column in *columns {
+            if let Some((other, ..)) = fields[..i].iter().find(|(_, prev, _)| prev.contains(column)) {
+                return Err((column, format!("column `{column}` is read by both `{other}` and `{field}`").into()));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Resolves the position of each column read by name, given with which of the columns sharing the name to read
+/// and the field position `fallback` falls back to.
+pub fn column_plan(
+    columns: &[&str],
+    fields: &[(&str, usize, usize)],
+    case_insensitive: bool,
+    fallback: bool,
+) -> Result<ColumnPlan, BoxError> {
+    plan_indices(columns, fields, case_insensitive, fallback).map(|indices| ColumnPlan { indices })
+}
+
+/// The positions [`column_plan`] resolves, from the names of the columns.
+fn plan_indices(
+    columns: &[&str],
+    fields: &[(&str, usize, usize)],
+    case_insensitive: bool,
+    fallback: bool,
+) -> Result<Vec<usize>, BoxError> {
+    fields
+        .iter()
+        .map(|&(name, nth, i)| {
+            let mut matches = columns.iter().enumerate().filter(|(_, column)| {
+                if case_insensitive {
+                    column.eq_ignore_ascii_case(name)
+                } else {
+                    **column == name
+                }
+            });
+            match matches.nth(nth) {
+                Some((index, _)) => Ok(index),
+                None if fallback => Ok(i),
+                None if nth > 0 => Err(format!("column `{name}` number {} not found", nth + 1).into()),
+                None => Err(format!("column `{name}` not found").into()),
+            }
+        })
+        .collect()
+}
+
+impl<T: FromRow> Flatten<T> for Row {
+    #[inline]
+    fn flatten(&self) -> Result<T, BoxError> {
+        T::from_row(self)
+    }
+}
+
+impl<'a, T> Flatten<T> for CompositeRow<'a>
+where
+    T: for<'r> TryFrom<&'r CompositeRow<'a>>,
+    for<'r> <T as TryFrom<&'r CompositeRow<'a>>>::Error: Into<BoxError>,
+{
+    #[inline]
+    fn flatten(&self) -> Result<T, BoxError> {
+        T::try_from(self).map_err(Into::into)
+    }
+}
+
+/// A field read with `#[column(bytea_base64)]`, either `String` or `Option<String>`, holding the base64 text of a `bytea` column.
+///
+/// `Insert` binds the decoded bytes of the field back, so it round-trips.
+#[cfg(feature = "base64")]
+#[diagnostic::on_unimplemented(message = "`#[column(bytea_base64)]` fields must be `String` or `Option<String>`, found `{Self}`")]
+pub trait Base64Field: Sized {
+    type Column: FromSqlOwned + ToSql;
+    fn from_column(column: Self::Column) -> Self;
+    fn to_column(&self) -> Result<Self::Column, BoxError>;
+}
+
+#[cfg(feature = "base64")]
+impl Base64Field for String {
+    type Column = Vec<u8>;
+    fn from_column(column: Vec<u8>) -> Self {
+        use base64::Engine;
+        base64::engine::general_purpose::STANDARD.encode(column)
+    }
+
+    fn to_column(&self) -> Result<Vec<u8>, BoxError> {
+        use base64::Engine;
+        Ok(base64::engine::general_purpose::STANDARD.decode(self)?)
+    }
+}
+
+#[cfg(feature = "base64")]
+impl Base64Field for Option<String> {
+    type Column = Option<Vec<u8>>;
+    fn from_column(column: Option<Vec<u8>>) -> Self {
+        column.map(String::from_column)
+    }
+
+    fn to_column(&self) -> Result<Option<Vec<u8>>, BoxError> {
+        self.as_ref().map(String::to_column).transpose()
+    }
+}
+
+/// A field read with `#[column(decimal)]`, either `Decimal` or `Option<Decimal>`.
+#[cfg_attr(
+    not(feature = "decimal"),
+    diagnostic::on_unimplemented(
+        message = "`#[column(decimal)]` requires the `decimal` feature of `tokio-postgres-utils`",
+        label = "enable the `decimal` feature"
+    )
+)]
+#[cfg_attr(
+    feature = "decimal",
+    diagnostic::on_unimplemented(
+        message = "`#[column(decimal)]` fields must be `rust_decimal::Decimal` or `Option<rust_decimal::Decimal>`, found `{Self}`"
+    )
+)]
+pub trait DecimalField: Sized {
+    type Column: FromSqlOwned;
+    fn from_column(column: Self::Column) -> Self;
+}
+
+/// A field read with `#[column(inet)]`, either `IpAddr` or `Option<IpAddr>`.
+#[diagnostic::on_unimplemented(message = "`#[column(inet)]` fields must be `std::net::IpAddr` or `Option<std::net::IpAddr>`, found `{Self}`")]
+pub trait InetField: Sized {
+    type Column: FromSqlOwned;
+    fn from_column(column: Self::Column) -> Self;
+}
+
+impl InetField for std::net::IpAddr {
+    type Column = Self;
+    fn from_column(column: Self) -> Self {
+        column
+    }
+}
+
+impl InetField for Option<std::net::IpAddr> {
+    type Column = Self;
+    fn from_column(column: Self) -> Self {
+        column
+    }
+}
+
+/// A field read with `#[column(uuid)]`, either `Uuid` or `Option<Uuid>`.
+#[cfg_attr(
+    not(feature = "uuid"),
+    diagnostic::on_unimplemented(
+        message = "`#[column(uuid)]` requires the `uuid` feature of `tokio-postgres-utils`",
+        label = "enable the `uuid` feature"
+    )
+)]
+#[cfg_attr(
+    feature = "uuid",
+    diagnostic::on_unimplemented(
+        message = "`#[column(uuid)]` fields must be `uuid::Uuid` or `Option<uuid::Uuid>`, found `{Self}`"
+    )
+)]
+pub trait UuidField: Sized {
+    type Column: FromSqlOwned;
+    fn from_column(column: Self::Column) -> Self;
+}
+
+#[cfg(feature = "uuid")]
+impl UuidField for uuid::Uuid {
+    type Column = Self;
+    fn from_column(column: Self) -> Self {
+        column
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl UuidField for Option<uuid::Uuid> {
+    type Column = Self;
+    fn from_column(column: Self) -> Self {
+        column
+    }
+}
+
+/// The text of a `json` or `jsonb` column, read by `#[column(json)]` fields.
+pub struct JsonColumn<'a>(pub(crate) &'a [u8]);
+
+impl<'a> FromSql<'a> for JsonColumn<'a> {
+    fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<Self, BoxError> {
+        if *ty == Type::JSONB {
+            return match raw.split_first() {
+                Some((1, text)) => Ok(JsonColumn(text)),
+                _ => Err("unsupported jsonb encoding version".into()),
+            };
+        }
+        Ok(JsonColumn(raw))
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        matches!(*ty, Type::JSON | Type::JSONB)
+    }
+}
+
+/// A field read with `#[column(json)]`, deserialized with `serde_json`. A `NULL` column is read as JSON `null`.
+#[cfg_attr(
+    not(feature = "json"),
+    diagnostic::on_unimplemented(
+        message = "`#[column(json)]` requires the `json` feature of `tokio-postgres-utils`",
+        label = "enable the `json` feature"
+    )
+)]
+#[cfg_attr(
+    feature = "json",
+    diagnostic::on_unimplemented(message = "`#[column(json)]` fields must implement `serde::de::DeserializeOwned`, found `{Self}`")
+)]
+pub trait JsonField: Sized {
+    fn from_json(field: &'static str, column: Option<JsonColumn<'_>>) -> Result<Self, BoxError>;
+}
+
+#[cfg(feature = "json")]
+impl<T: serde::de::DeserializeOwned> JsonField for T {
+    fn from_json(field: &'static str, column: Option<JsonColumn<'_>>) -> Result<Self, BoxError> {
+        let text = column.map_or(&b"null"[..], |column| column.0);
+        serde_json::from_slice(text).map_err(|err| format!("error decoding json field `{field}`: {err}").into())
+    }
+}
+
+#[cfg(feature = "decimal")]
+pub use decimal::DecimalColumn;
+
+#[cfg(feature = "decimal")]
+mod decimal {
+    use super::{BoxError, DecimalField};
+    use rust_decimal::Decimal;
+    use tokio_postgres::types::{FromSql, Type};
+
+    /// A `numeric` or `money` value. `money` is read as an amount with 2 decimal places.
+    pub struct DecimalColumn(Decimal);
+
+    impl<'a> FromSql<'a> for DecimalColumn {
+        fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<Self, BoxError> {
+            if *ty == Type::MONEY {
+                let cents = i64::from_be_bytes(raw.try_into()?);
+                return Ok(DecimalColumn(Decimal::new(cents, 2)));
+            }
+            Decimal::from_sql(ty, raw).map(DecimalColumn)
+        }
+
+        fn accepts(ty: &Type) -> bool {
+            *ty == Type::MONEY || <Decimal as FromSql>::accepts(ty)
+        }
+    }
+
+    impl DecimalField for Decimal {
+        type Column = DecimalColumn;
+        fn from_column(column: DecimalColumn) -> Self {
+            column.0
+        }
+    }
+
+    impl DecimalField for Option<Decimal> {
+        type Column = Option<DecimalColumn>;
+        fn from_column(column: Option<DecimalColumn>) -> Self {
+            column.map(|column| column.0)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::plan_indices;
+
+    const FIELDS: [(&str, usize, usize); 2] = [("id", 0, 0), ("name", 0, 1)];
+
+    #[test]
+    fn plans_columns_by_name() {
+        assert_eq!(plan_indices(&["other", "name", "id"], &FIELDS, false, false).unwrap(), [2, 1]);
+        // The first of the columns sharing a name, unless a later one is asked for.
+        assert_eq!(plan_indices(&["id", "name", "id"], &FIELDS, false, false).unwrap(), [0, 1]);
+        assert_eq!(plan_indices(&["id", "name", "id"], &[("id", 1, 0)], false, false).unwrap(), [2]);
+    }
+
+    #[test]
+    fn plans_case_insensitive_columns() {
+        assert!(plan_indices(&["NAME", "Id"], &FIELDS, false, false).is_err());
+        assert_eq!(plan_indices(&["NAME", "Id"], &FIELDS, true, false).unwrap(), [1, 0]);
+    }
+
+    #[test]
+    fn falls_back_to_field_positions() {
+        assert_eq!(plan_indices(&["a", "b"], &FIELDS, false, true).unwrap(), [0, 1]);
+        assert_eq!(plan_indices(&["a", "id"], &FIELDS, false, true).unwrap(), [1, 1]);
+    }
+
+    #[test]
+    fn names_missing_columns() {
+        let err = plan_indices(&["id"], &FIELDS, false, false).unwrap_err();
+        assert_eq!(err.to_string(), "column `name` not found");
+        let err = plan_indices(&["id"], &[("id", 1, 0)], false, false).unwrap_err();
+        assert_eq!(err.to_string(), "column `id` number 2 not found");
+    }
+
+    #[cfg(feature = "decimal")]
+    mod decimal {
+        use crate::__private::{DecimalColumn, DecimalField};
+        use rust_decimal::Decimal;
+        use tokio_postgres::types::{FromSql, Type};
+
+        fn decode(ty: &Type, raw: &[u8]) -> Decimal {
+            Decimal::from_column(DecimalColumn::from_sql(ty, raw).unwrap())
+        }
+
+        #[test]
+        fn reads_numeric() {
+            // 12.34: 2 base 10000 digits, weight 0, positive, scale 2, digits 12 and 3400.
+            let raw = [0, 2, 0, 0, 0, 0, 0, 2, 0, 12, 0x0d, 0x48];
+            assert_eq!(decode(&Type::NUMERIC, &raw), Decimal::new(1234, 2));
+        }
+
+        #[test]
+        fn reads_money_as_cents() {
+            assert_eq!(decode(&Type::MONEY, &(-1234i64).to_be_bytes()), Decimal::new(-1234, 2));
+            assert_eq!(decode(&Type::MONEY, &5i64.to_be_bytes()).to_string(), "0.05");
+            assert!(DecimalColumn::from_sql(&Type::MONEY, &[0; 4]).is_err());
+        }
+
+        #[test]
+        fn reads_null_as_none() {
+            let column = <Option<DecimalColumn>>::from_sql_nullable(&Type::MONEY, None).unwrap();
+            assert_eq!(<Option<Decimal>>::from_column(column), None);
+        }
+
+        #[test]
+        fn accepts_numeric_and_money() {
+            assert!(<DecimalColumn as FromSql>::accepts(&Type::NUMERIC));
+            assert!(<DecimalColumn as FromSql>::accepts(&Type::MONEY));
+            assert!(!<DecimalColumn as FromSql>::accepts(&Type::INT8));
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use crate::BoxError;
+use std::{marker::PhantomData, slice};
+use tokio_postgres::{Column, Row};
+
+/// Column positions of the fields of a struct, resolved once from a statement's columns.
+pub struct ColumnPlan {
+    pub(crate) indices: Vec<usize>,
+}
+
+impl ColumnPlan {
+    /// Column position of each field read by name, in field order.
+    ///
+    /// Fields that don't read a column by name, such as skipped or flattened ones, have no entry,
+    /// so the positions line up with the struct's `columns()`:
+    ///
+    /// ```rust, no_run
+    /// use tokio_postgres_utils::{FromRow, FromRowPlan, RowBatch, TryFromRow};
+    ///
+    /// #[derive(TryFromRow)]
+    /// struct User {
+    ///     #[column(skip)]
+    ///     cache: Option<String>,
+    ///     id: i32,
+    ///     #[column(skip)]
+    ///     seen: bool,
+    ///     name: String,
+    /// }
+    ///
+    /// # async fn example(client: &tokio_postgres::Client) -> Result<(), tokio_postgres_utils::BoxError> {
+    /// let rows = client.query("SELECT 'nur' AS name, 1 AS id", &[]).await?;
+    /// let plan = User::column_plan(rows[0].columns())?;
+    /// assert_eq!(<User as FromRow>::columns(), ["id", "name"]);
+    /// assert_eq!(plan.indices(), [1, 0]);
+    ///
+    /// let user = User::from_row_with_plan(&rows[0], &plan)?;
+    /// assert_eq!((user.cache, user.id, user.seen, user.name.as_str()), (None, 1, false, "nur"));
+    /// assert_eq!(RowBatch::<User>::new(&rows).next().unwrap()?.name, "nur");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn indices(&self) -> &[usize] {
+        &self.indices
+    }
+}
+
+/// Decoding with a [`ColumnPlan`], implemented by `#[derive(TryFromRow)]`.
+///
+/// Decoding by name looks every column up on every row, a plan resolves them once per statement,
+/// which pays off for wide structs mapped in bulk. See [`RowBatch`].
+pub trait FromRowPlan: Sized {
+    /// Resolves the position of every column the struct reads.
+    fn column_plan(columns: &[Column]) -> Result<ColumnPlan, BoxError> {
+        let names: Vec<&str> = columns.iter().map(Column::name).collect();
+        Self::column_plan_by_name(&names)
+    }
+
+    /// Resolves the positions from the names of the columns alone, such as those of a fake row in tests.
+    fn column_plan_by_name(columns: &[&str]) -> Result<ColumnPlan, BoxError>;
+
+    /// Reads `Self` from `row`, using the column positions of `plan`.
+    ///
+    /// `plan` must come from the columns of the statement `row` belongs to.
+    fn from_row_with_plan(row: &Row, plan: &ColumnPlan) -> Result<Self, BoxError>;
+}
+
+/// Decodes rows of the same statement into `T`, resolving the column positions once from the first row.
+///
+/// ## Example
+///
+/// ```rust, no_run
+/// use tokio_postgres_utils::{RowBatch, TryFromRow};
+///
+/// #[derive(TryFromRow)]
+/// struct User {
+///     id: i32,
+///     name: String,
+/// }
+///
+/// # async fn example(client: &tokio_postgres::Client) -> Result<(), tokio_postgres_utils::BoxError> {
+/// let rows = client.query("SELECT name, id FROM users", &[]).await?;
+/// let users = RowBatch::<User>::new(&rows).collect::<Result<Vec<_>, _>>()?;
+///
+/// // Same result as decoding each row by name.
+/// let naive = rows.iter().map(User::try_from).collect::<Result<Vec<_>, _>>()?;
+/// assert!(users.iter().zip(&naive).all(|(a, b)| a.id == b.id && a.name == b.name));
+/// # Ok(())
+/// # }
+/// ```
+pub struct RowBatch<'a, T> {
+    rows: slice::Iter<'a, Row>,
+    plan: Option<ColumnPlan>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<'a, T: FromRowPlan> RowBatch<'a, T> {
+    /// Creates a batch over `rows`, which must all come from the same statement.
+    pub fn new(rows: &'a [Row]) -> Self {
+        Self {
+            rows: rows.iter(),
+            plan: None,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<T: FromRowPlan> Iterator for RowBatch<'_, T> {
+    type Item = Result<T, BoxError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let row = self.rows.next()?;
+        let plan = match &self.plan {
+            Some(plan) => plan,
+            None => match T::column_plan(row.columns()) {
+                Ok(plan) => self.plan.insert(plan),
+                Err(err) => return Some(Err(err)),
+            },
+        };
+        Some(T::from_row_with_plan(row, plan))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.rows.size_hint()
+    }
+}
@@ -0,0 +1,164 @@
+use crate::BoxError;
+use std::fmt;
+use tokio_postgres::types::{Field, FromSql, Kind, Type};
+
+/// A decoded value of a composite type, exposing its fields like a [`Row`](tokio_postgres::Row) does.
+///
+/// Structs deriving `TryFromRow` with the `#[column(composite)]` container attribute also implement
+/// `TryFrom<&CompositeRow>`, so they can be read from composite values, such as `array_agg(child)`.
+pub struct CompositeRow<'a> {
+    fields: Vec<(&'a Field, Option<&'a [u8]>)>,
+}
+
+impl<'a> CompositeRow<'a> {
+    /// Decodes the binary representation of a composite value of type `ty`.
+    pub fn parse(ty: &'a Type, mut raw: &'a [u8]) -> Result<Self, BoxError> {
+        let Kind::Composite(fields) = ty.kind() else {
+            return Err(format!("`{}` is not a composite type", ty.name()).into());
+        };
+        let count = read_i32(&mut raw)?;
+        if count as usize != fields.len() {
+            return Err(format!("expected {} fields, found {count}", fields.len()).into());
+        }
+        let mut values = Vec::with_capacity(fields.len());
+        for field in fields {
+            let _oid = read_i32(&mut raw)?;
+            let len = read_i32(&mut raw)?;
+            let value = if len < 0 {
+                None
+            } else {
+                let (value, rest) = raw.split_at_checked(len as usize).ok_or("invalid composite value")?;
+                raw = rest;
+                Some(value)
+            };
+            values.push((field, value));
+        }
+        Ok(Self { fields: values })
+    }
+
+    /// Returns the number of fields.
+    pub fn len(&self) -> usize {
+        self.fields.len()
+    }
+
+    /// Returns `true` if the composite value has no fields.
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    /// Deserializes a field by name or position.
+    pub fn try_get<I, T>(&self, idx: I) -> Result<T, BoxError>
+    where
+        I: CompositeIndex + fmt::Display,
+        T: FromSql<'a>,
+    {
+        let (field, raw) = idx
+            .index(self)
+            .and_then(|i| self.fields.get(i))
+            .ok_or_else(|| format!("invalid field `{idx}`"))?;
+        if !T::accepts(field.type_()) {
+            return Err(format!(
+                "cannot convert field `{idx}` of type `{}` into `{}`",
+                field.type_(),
+                std::any::type_name::<T>()
+            )
+            .into());
+        }
+        T::from_sql_nullable(field.type_(), *raw)
+    }
+
+    /// Like [`CompositeRow::try_get`], but panics on failure.
+    #[track_caller]
+    pub fn get<I, T>(&self, idx: I) -> T
+    where
+        I: CompositeIndex + fmt::Display,
+        T: FromSql<'a>,
+    {
+        match self.try_get(idx) {
+            Ok(value) => value,
+            Err(err) => panic!("error retrieving composite field: {err}"),
+        }
+    }
+}
+
+/// A field index of a [`CompositeRow`], either a name or a position.
+pub trait CompositeIndex {
+    /// Returns the position of the field.
+    fn index(&self, row: &CompositeRow) -> Option<usize>;
+}
+
+impl CompositeIndex for usize {
+    fn index(&self, row: &CompositeRow) -> Option<usize> {
+        (*self < row.len()).then_some(*self)
+    }
+}
+
+impl CompositeIndex for str {
+    fn index(&self, row: &CompositeRow) -> Option<usize> {
+        row.fields.iter().position(|(field, _)| field.name() == self)
+    }
+}
+
+impl<T: CompositeIndex + ?Sized> CompositeIndex for &T {
+    fn index(&self, row: &CompositeRow) -> Option<usize> {
+        T::index(self, row)
+    }
+}
+
+/// Decodes a composite value into `T` through its `TryFrom<&CompositeRow>` impl.
+///
+/// `Vec<Composite<T>>` decodes arrays of composites, e.g. the result of `array_agg(child)`.
+///
+/// ## Example
+///
+/// ```rust
+/// use tokio_postgres::types::{Field, FromSql, Kind, Type};
+/// use tokio_postgres_utils::{Composite, TryFromRow};
+///
+/// #[derive(TryFromRow)]
+/// #[column(composite)]
+/// struct Child {
+///     id: i32,
+///     name: String,
+/// }
+///
+/// let ty = Type::new(
+///     "child".into(),
+///     16400,
+///     Kind::Composite(vec![
+///         Field::new("id".into(), Type::INT4),
+///         Field::new("name".into(), Type::TEXT),
+///     ]),
+///     "public".into(),
+/// );
+///
+/// let mut raw = vec![0, 0, 0, 2];
+/// raw.extend([0, 0, 0, 23, 0, 0, 0, 4, 0, 0, 0, 7]);
+/// raw.extend([0, 0, 0, 25, 0, 0, 0, 3, b'N', b'u', b'r']);
+///
+/// let child = Composite::<Child>::from_sql(&ty, &raw).unwrap().0;
+/// assert_eq!(child.id, 7);
+/// assert_eq!(child.name, "Nur");
+/// ```
+pub struct Composite<T>(pub T);
+
+impl<'a, T> FromSql<'a> for Composite<T>
+where
+    T: for<'r> TryFrom<&'r CompositeRow<'r>>,
+    for<'r> <T as TryFrom<&'r CompositeRow<'r>>>::Error: Into<BoxError>,
+{
+    fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<Self, BoxError> {
+        let row = CompositeRow::parse(ty, raw)?;
+        T::try_from(&row).map(Composite).map_err(Into::into)
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        matches!(ty.kind(), Kind::Composite(_))
+    }
+}
+
+fn read_i32(raw: &mut &[u8]) -> Result<i32, BoxError> {
+    let (bytes, rest) = raw.split_first_chunk::<4>().ok_or("invalid composite value")?;
+    *raw = rest;
+    Ok(i32::from_be_bytes(*bytes))
+}
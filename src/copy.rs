@@ -0,0 +1,102 @@
+use crate::{BoxError, Insert};
+use futures_util::{Stream, StreamExt};
+use std::pin::pin;
+use tokio_postgres::{
+    binary_copy::{BinaryCopyInWriter, BinaryCopyOutRow, BinaryCopyOutStream},
+    types::Type,
+    GenericClient, ToStatement,
+};
+
+/// Runs a `COPY ... TO STDOUT (FORMAT binary)` statement, decoding each row into `T`.
+///
+/// The binary `COPY` format carries neither column names nor types, so `types` lists the column types in order,
+/// and `T` reads its fields by position. Structs get the required `TryFrom<&BinaryCopyOutRow>` impl by deriving
+/// `TryFromRow` with the `#[column(binary_copy)]` container attribute.
+///
+/// ## Example
+///
+/// ```rust, no_run
+/// use futures_util::TryStreamExt;
+/// use tokio_postgres::types::Type;
+/// use tokio_postgres_utils::{copy_out_as, TryFromRow};
+///
+/// #[derive(TryFromRow)]
+/// #[column(binary_copy)]
+/// struct User {
+///     id: i32,
+///     name: String,
+/// }
+///
+/// # async fn example(client: &tokio_postgres::Client) -> Result<(), tokio_postgres_utils::BoxError> {
+/// let users: Vec<User> = copy_out_as(
+///     client,
+///     "COPY (SELECT id, name FROM users) TO STDOUT (FORMAT binary)",
+///     &[Type::INT4, Type::TEXT],
+/// )
+/// .await?
+/// .try_collect()
+/// .await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn copy_out_as<T>(
+    client: &impl GenericClient,
+    statement: &(impl ToStatement + ?Sized),
+    types: &[Type],
+) -> Result<impl Stream<Item = Result<T, BoxError>>, BoxError>
+where
+    T: for<'a> TryFrom<&'a BinaryCopyOutRow>,
+    for<'a> <T as TryFrom<&'a BinaryCopyOutRow>>::Error: Into<BoxError>,
+{
+    let stream = client.client().copy_out(statement).await?;
+    Ok(BinaryCopyOutStream::new(stream, types).map(|row| T::try_from(&row?).map_err(Into::into)))
+}
+
+/// Bulk loads rows into `table` with `COPY ... FROM STDIN (FORMAT binary)`, returning the number of rows written.
+///
+/// Each row is written through its [`Insert`] impl, in [`Insert::COLUMNS`] order.
+/// The column types are looked up from the table beforehand.
+/// This is much faster than inserting rows one by one for large batches.
+///
+/// ## Example
+///
+/// ```rust, no_run
+/// use tokio_postgres_utils::{copy_in_as, Insert};
+///
+/// #[derive(Insert)]
+/// struct NewUser {
+///     id: i32,
+///     name: String,
+/// }
+///
+/// # async fn example(client: &tokio_postgres::Client) -> Result<(), tokio_postgres_utils::BoxError> {
+/// let users = (0..10_000).map(|id| NewUser { id, name: format!("user {id}") });
+/// let written = copy_in_as(client, "users", users).await?;
+/// assert_eq!(written, 10_000);
+/// # Ok(())
+/// # }
+/// ```
+pub async fn copy_in_as<T: Insert>(
+    client: &impl GenericClient,
+    table: &str,
+    rows: impl IntoIterator<Item = T>,
+) -> Result<u64, BoxError> {
+    let columns = T::column_list();
+    let types: Vec<Type> = client
+        .prepare(&format!("SELECT {columns} FROM {table}"))
+        .await?
+        .columns()
+        .iter()
+        .map(|column| column.type_().clone())
+        .collect();
+
+    let sink = client
+        .client()
+        .copy_in(&format!("COPY {table} ({columns}) FROM STDIN (FORMAT binary)"))
+        .await?;
+    let mut writer = pin!(BinaryCopyInWriter::new(sink, &types));
+    for row in rows {
+        writer.as_mut().write(&row.as_params()).await?;
+    }
+    Ok(writer.finish().await?)
+}
@@ -0,0 +1,135 @@
+use crate::{__private::quote_ident, BoxError, FromRow};
+use std::marker::PhantomData;
+use tokio_postgres::{
+    types::{FromSqlOwned, ToSql},
+    GenericClient,
+};
+
+/// Pages through the rows of a query by keyset, in the order of one unique `column`, mapping each row into `T`.
+///
+/// Each page is selected from the query as a subquery, after the `column` value of the last row read so far,
+/// which is bound as a param. Unlike `OFFSET`, a page costs the same however deep it is,
+/// and rows inserted meanwhile before the cursor never shift the next pages.
+///
+/// ```rust, no_run
+/// use tokio_postgres_utils::{Cursor, TryFromRow};
+///
+/// #[derive(TryFromRow)]
+/// struct Item {
+///     id: i32,
+/// }
+///
+/// # async fn example(client: &tokio_postgres::Client) -> Result<(), tokio_postgres_utils::BoxError> {
+/// let mut cursor = Cursor::<_, Item, i32>::new(client, "SELECT n AS id FROM generate_series(1, 25) AS n", "id", 10);
+///
+/// let mut pages = Vec::new();
+/// while let Some(page) = cursor.next_page().await? {
+///     pages.push(page.iter().map(|item| item.id).collect::<Vec<_>>());
+/// }
+/// assert_eq!(pages.iter().map(Vec::len).collect::<Vec<_>>(), [10, 10, 5]);
+/// assert_eq!(pages[2], [21, 22, 23, 24, 25]);
+///
+/// let mut cursor = Cursor::<_, Item, i32>::new(client, "SELECT n AS id FROM generate_series(1, 20) AS n", "id", 10).after(15);
+/// assert_eq!(cursor.next_page().await?.unwrap().len(), 5);
+/// assert!(cursor.next_page().await?.is_none());
+/// # Ok(())
+/// # }
+/// ```
+pub struct Cursor<'a, C, T, K> {
+    client: &'a C,
+    query: String,
+    column: String,
+    page_size: usize,
+    last: Option<K>,
+    done: bool,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<'a, C, T, K> Cursor<'a, C, T, K>
+where
+    C: GenericClient + Sync,
+    T: FromRow,
+    K: FromSqlOwned + ToSql + Send + Sync,
+{
+    /// Creates a cursor over the rows of `query`, fetching `page_size` rows at a time in ascending `column` order.
+    ///
+    /// The column must be unique, and is quoted, so it must be spelled as the query names it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `page_size` is 0.
+    pub fn new(client: &'a C, query: &str, column: &str, page_size: usize) -> Self {
+        assert!(page_size > 0, "the page size of a `Cursor` must be at least 1");
+        Cursor {
+            client,
+            query: query.to_owned(),
+            column: column.to_owned(),
+            page_size,
+            last: None,
+            done: false,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Starts the cursor after the row whose column is `key`, e.g. to resume from the last key a client has seen.
+    pub fn after(mut self, key: K) -> Self {
+        self.last = Some(key);
+        self
+    }
+
+    /// Fetches the next page, or `None` once every row has been read.
+    ///
+    /// A page is never empty. A page shorter than the page size is the last one, so it's followed by `None` without another query.
+    pub async fn next_page(&mut self) -> Result<Option<Vec<T>>, BoxError> {
+        if self.done {
+            return Ok(None);
+        }
+        let sql = page_sql(&self.query, &self.column, self.page_size, self.last.is_some());
+        let rows = match &self.last {
+            Some(last) => self.client.query(&sql, &[last]).await?,
+            None => self.client.query(&sql, &[]).await?,
+        };
+        self.done = rows.len() < self.page_size;
+        let Some(last) = rows.last() else {
+            return Ok(None);
+        };
+        self.last = Some(last.try_get(self.column.as_str())?);
+        rows.iter().map(T::from_row).collect::<Result<_, _>>().map(Some)
+    }
+}
+
+/// Selects a page of `limit` rows of `query`, after the `column` value bound as `$1` if `after` is set.
+fn page_sql(query: &str, column: &str, limit: usize, after: bool) -> String {
+    let column = quote_ident(column);
+    let filter = if after { format!(" WHERE __page.{column} > $1") } else { String::new() };
+    format!("SELECT * FROM ({query}) AS __page{filter} ORDER BY __page.{column} LIMIT {limit}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::page_sql;
+
+    #[test]
+    fn selects_the_first_page() {
+        assert_eq!(
+            page_sql("SELECT id FROM items", "id", 10, false),
+            r#"SELECT * FROM (SELECT id FROM items) AS __page ORDER BY __page."id" LIMIT 10"#
+        );
+    }
+
+    #[test]
+    fn selects_after_the_last_key() {
+        assert_eq!(
+            page_sql("SELECT id FROM items", "id", 10, true),
+            r#"SELECT * FROM (SELECT id FROM items) AS __page WHERE __page."id" > $1 ORDER BY __page."id" LIMIT 10"#
+        );
+    }
+
+    #[test]
+    fn quotes_the_column() {
+        assert_eq!(
+            page_sql("SELECT 1 AS \"Odd\"\"Id\"", "Odd\"Id", 1, false),
+            r#"SELECT * FROM (SELECT 1 AS "Odd""Id") AS __page ORDER BY __page."Odd""Id" LIMIT 1"#
+        );
+    }
+}
@@ -0,0 +1,122 @@
+use crate::BoxError;
+use std::fmt::Write;
+use tokio_postgres::types::Type;
+
+/// Days from the Unix epoch to 2000-01-01, the epoch of Postgres dates and timestamps.
+const PG_EPOCH_DAYS: i64 = 10_957;
+
+const MICROS_PER_DAY: i64 = 86_400_000_000;
+
+/// Whether [`format`] can write values of `ty`.
+pub(crate) fn accepts(ty: &Type) -> bool {
+    matches!(*ty, Type::DATE | Type::TIME | Type::TIMESTAMP | Type::TIMESTAMPTZ)
+}
+
+/// Writes a binary `date`, `time`, `timestamp` or `timestamptz` value in the RFC 3339 format, like `2000-01-02T03:04:05.5Z`.
+///
+/// `timestamptz` values are sent in UTC and written with a `Z`, `timestamp` values have no offset to write.
+/// Infinite dates and timestamps are written as `infinity` and `-infinity`, as Postgres does.
+pub(crate) fn format(ty: &Type, raw: &[u8]) -> Result<String, BoxError> {
+    let mut out = String::new();
+    match *ty {
+        Type::DATE => match i32::from_be_bytes(raw.try_into()?) {
+            i32::MAX => out.push_str("infinity"),
+            i32::MIN => out.push_str("-infinity"),
+            days => write_date(&mut out, days.into()),
+        },
+        Type::TIME => write_time(&mut out, i64::from_be_bytes(raw.try_into()?)),
+        Type::TIMESTAMP | Type::TIMESTAMPTZ => match i64::from_be_bytes(raw.try_into()?) {
+            i64::MAX => out.push_str("infinity"),
+            i64::MIN => out.push_str("-infinity"),
+            micros => {
+                write_date(&mut out, micros.div_euclid(MICROS_PER_DAY));
+                out.push('T');
+                write_time(&mut out, micros.rem_euclid(MICROS_PER_DAY));
+                if *ty == Type::TIMESTAMPTZ {
+                    out.push('Z');
+                }
+            }
+        },
+        _ => return Err(format!("`{ty}` is not a date or time type").into()),
+    }
+    Ok(out)
+}
+
+/// Writes the date `days` after 2000-01-01 as `YYYY-MM-DD`, with a sign for years outside of 0 to 9999.
+fn write_date(out: &mut String, days: i64) {
+    // The civil date of a day count, from Howard Hinnant's `civil_from_days`.
+    let z = days + PG_EPOCH_DAYS + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    let _ = if (0..=9999).contains(&year) {
+        write!(out, "{year:04}-{month:02}-{day:02}")
+    } else {
+        write!(out, "{year:+05}-{month:02}-{day:02}")
+    };
+}
+
+/// Writes `micros` after midnight as `HH:MM:SS`, with the fraction of a second when there is one.
+fn write_time(out: &mut String, micros: i64) {
+    let secs = micros / 1_000_000;
+    let _ = write!(out, "{:02}:{:02}:{:02}", secs / 3_600, secs / 60 % 60, secs % 60);
+    let fraction = micros % 1_000_000;
+    if fraction != 0 {
+        let _ = write!(out, ".{fraction:06}");
+        out.truncate(out.trim_end_matches('0').len());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn timestamp(ty: Type, micros: i64) -> String {
+        format(&ty, &micros.to_be_bytes()).unwrap()
+    }
+
+    fn date(days: i32) -> String {
+        format(&Type::DATE, &days.to_be_bytes()).unwrap()
+    }
+
+    #[test]
+    fn dates() {
+        assert_eq!(date(0), "2000-01-01");
+        assert_eq!(date(59), "2000-02-29");
+        assert_eq!(date(-10_957), "1970-01-01");
+        assert_eq!(date(-1), "1999-12-31");
+        assert_eq!(date(8_766), "2024-01-01");
+        assert_eq!(date(2_921_940), "+10000-01-01");
+        assert_eq!(date(-730_485), "0000-01-01");
+        assert_eq!(date(-730_486), "-0001-12-31");
+        assert_eq!(date(i32::MAX), "infinity");
+        assert_eq!(date(i32::MIN), "-infinity");
+    }
+
+    #[test]
+    fn timestamps() {
+        assert_eq!(timestamp(Type::TIMESTAMPTZ, 0), "2000-01-01T00:00:00Z");
+        assert_eq!(timestamp(Type::TIMESTAMP, 0), "2000-01-01T00:00:00");
+        assert_eq!(timestamp(Type::TIMESTAMPTZ, 97_445_500_000), "2000-01-02T03:04:05.5Z");
+        assert_eq!(timestamp(Type::TIMESTAMP, -1), "1999-12-31T23:59:59.999999");
+        assert_eq!(timestamp(Type::TIMESTAMPTZ, i64::MAX), "infinity");
+        assert_eq!(timestamp(Type::TIMESTAMP, i64::MIN), "-infinity");
+    }
+
+    #[test]
+    fn times() {
+        assert_eq!(timestamp(Type::TIME, 0), "00:00:00");
+        assert_eq!(timestamp(Type::TIME, 86_399_000_001), "23:59:59.000001");
+    }
+
+    #[test]
+    fn rejects_other_types_and_sizes() {
+        assert!(format(&Type::INT8, &0i64.to_be_bytes()).is_err());
+        assert!(format(&Type::DATE, &0i64.to_be_bytes()).is_err());
+    }
+}
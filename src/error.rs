@@ -0,0 +1,59 @@
+use crate::BoxError;
+use std::{error::Error, fmt};
+
+/// Every column that failed to decode, returned by `TryFromRow` structs with `#[column(collect_errors)]`.
+#[derive(Debug, Default)]
+pub struct MultiDecodeError {
+    pub(crate) errors: Vec<(&'static str, BoxError)>,
+}
+
+impl MultiDecodeError {
+    /// The failed columns, in field order, with their errors.
+    ///
+    /// Fields that don't read a single column, such as `flatten` fields, are reported by their field name.
+    pub fn errors(&self) -> &[(&'static str, BoxError)] {
+        &self.errors
+    }
+}
+
+impl fmt::Display for MultiDecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "failed to decode {} column(s)", self.errors.len())?;
+        for (i, (column, err)) in self.errors.iter().enumerate() {
+            let sep = if i == 0 { ": " } else { "; " };
+            write!(f, "{sep}`{column}`: {err}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for MultiDecodeError {}
+
+/// The column a field failed to decode, returned by `TryFromRow` structs with the `error-context` feature
+/// from `FromRow::from_row`, and from `try_from` when its error is boxed.
+///
+/// The column is named as read from the row, after `rename` and `rename_all`.
+#[derive(Debug)]
+pub struct ColumnError {
+    pub(crate) column: &'static str,
+    pub(crate) source: BoxError,
+}
+
+impl ColumnError {
+    /// The column that failed to decode.
+    pub fn column(&self) -> &'static str {
+        self.column
+    }
+}
+
+impl fmt::Display for ColumnError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "error decoding column `{}`: {}", self.column, self.source)
+    }
+}
+
+impl Error for ColumnError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&*self.source)
+    }
+}
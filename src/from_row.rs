@@ -0,0 +1,276 @@
+use crate::{BoxError, __private};
+use std::{collections::HashMap, error::Error, fmt, hash::Hash, rc::Rc, sync::Arc};
+use tokio_postgres::{
+    types::{FromSql, FromSqlOwned, Type},
+    Row,
+};
+
+/// Reads a struct from a [`Row`], implemented by `#[derive(FromRow)]` and `#[derive(TryFromRow)]`.
+///
+/// `#[column(flatten)]` fields are read through this trait, so a flatten target can also implement it by hand.
+///
+/// Tuples of up to 8 `FromRow` types implement it too, each element reading its own columns from the same row,
+/// so a joined row can be mapped into e.g. `(User, Order)`. Columns with the same name in both tables must be
+/// aliased apart and renamed, as each element reads the first column with a given name.
+///
+/// ## Example
+///
+/// ```rust
+/// use tokio_postgres::Row;
+/// use tokio_postgres_utils::{BoxError, FromRow, TryFromRow};
+///
+/// struct Money {
+///     cents: i64,
+/// }
+///
+/// impl FromRow for Money {
+///     fn from_row(row: &Row) -> Result<Self, BoxError> {
+///         let units: i64 = row.try_get("units")?;
+///         let cents: i64 = row.try_get("cents")?;
+///         Ok(Money { cents: units * 100 + cents })
+///     }
+/// }
+///
+/// #[derive(TryFromRow)]
+/// struct Order {
+///     id: i32,
+///     #[column(flatten)]
+///     total: Money,
+/// }
+/// ```
+pub trait FromRow: Sized {
+    /// Reads `Self` from `row`.
+    fn from_row(row: &Row) -> Result<Self, BoxError>;
+
+    /// Columns read by name, not counting those of nested `flatten` fields.
+    ///
+    /// A flattened `Option<Self>` is `None` when all of them are `NULL`, as in the result of a `LEFT JOIN` without a match.
+    fn columns() -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Whether the field reading `column` accepts a column of type `ty`, `None` if it can't tell.
+    ///
+    /// Used by [`validate_struct_against`](crate::validate_struct_against) to find type mismatches.
+    fn accepts_column(column: &str, ty: &Type) -> Option<bool> {
+        let _ = (column, ty);
+        None
+    }
+}
+
+macro_rules! impl_from_row_for_wrapper {
+    ($($wrapper:ident),*) => {$(
+        impl<T: FromRow> FromRow for $wrapper<T> {
+            #[inline]
+            fn from_row(row: &Row) -> Result<Self, BoxError> {
+                T::from_row(row).map($wrapper::new)
+            }
+
+            #[inline]
+            fn columns() -> &'static [&'static str] {
+                T::columns()
+            }
+
+            #[inline]
+            fn accepts_column(column: &str, ty: &Type) -> Option<bool> {
+                T::accepts_column(column, ty)
+            }
+        }
+    )*};
+}
+
+impl_from_row_for_wrapper!(Box, Rc, Arc);
+
+macro_rules! impl_from_row_for_tuple {
+    ($($name:ident),*) => {
+        impl<$($name: FromRow),*> FromRow for ($($name,)*) {
+            #[inline]
+            fn from_row(row: &Row) -> Result<Self, BoxError> {
+                Ok(($($name::from_row(row)?,)*))
+            }
+        }
+    };
+}
+
+impl_from_row_for_tuple!(T0, T1);
+impl_from_row_for_tuple!(T0, T1, T2);
+impl_from_row_for_tuple!(T0, T1, T2, T3);
+impl_from_row_for_tuple!(T0, T1, T2, T3, T4);
+impl_from_row_for_tuple!(T0, T1, T2, T3, T4, T5);
+impl_from_row_for_tuple!(T0, T1, T2, T3, T4, T5, T6);
+impl_from_row_for_tuple!(T0, T1, T2, T3, T4, T5, T6, T7);
+
+impl<T: FromRow> FromRow for Option<T> {
+    fn from_row(row: &Row) -> Result<Self, BoxError> {
+        let columns = T::columns();
+        if !columns.is_empty() && columns.iter().all(|column| __private::is_null(row, *column)) {
+            return Ok(None);
+        }
+        T::from_row(row).map(Some)
+    }
+
+    #[inline]
+    fn columns() -> &'static [&'static str] {
+        T::columns()
+    }
+
+    #[inline]
+    fn accepts_column(column: &str, ty: &Type) -> Option<bool> {
+        T::accepts_column(column, ty)
+    }
+}
+
+macro_rules! impl_from_row_for_result {
+    ($($err:ty),*) => {$(
+        /// Keeps the error of `T` instead of failing, so a flattened `Result<T, _>` field
+        /// lets one bad nested group leave the rest of the row readable.
+        impl<T: FromRow> FromRow for Result<T, $err> {
+            #[inline]
+            fn from_row(row: &Row) -> Result<Self, BoxError> {
+                Ok(T::from_row(row).map_err(|err| err as $err))
+            }
+
+            #[inline]
+            fn columns() -> &'static [&'static str] {
+                T::columns()
+            }
+
+            #[inline]
+            fn accepts_column(column: &str, ty: &Type) -> Option<bool> {
+                T::accepts_column(column, ty)
+            }
+        }
+    )*};
+}
+
+impl_from_row_for_result!(BoxError, Box<dyn Error + Send>, Box<dyn Error>);
+
+/// Maps every row into `T`, returning the index of the first row that fails alongside its error.
+///
+/// Handy to track down the one bad row of a large batch.
+///
+/// ```rust, no_run
+/// use tokio_postgres_utils::{try_from_rows_indexed, TryFromRow};
+///
+/// #[derive(TryFromRow)]
+/// struct User {
+///     id: i32,
+/// }
+///
+/// # async fn example(client: &tokio_postgres::Client) -> Result<(), tokio_postgres_utils::BoxError> {
+/// let rows = client.query("SELECT * FROM (VALUES (1), (2), (NULL), (4)) AS t(id)", &[]).await?;
+/// let (index, _err) = try_from_rows_indexed::<User>(&rows).err().unwrap();
+/// assert_eq!(index, 2);
+/// # Ok(())
+/// # }
+/// ```
+pub fn try_from_rows_indexed<T: FromRow>(rows: &[Row]) -> Result<Vec<T>, (usize, BoxError)> {
+    rows.iter()
+        .enumerate()
+        .map(|(i, row)| T::from_row(row).map_err(|err| (i, err)))
+        .collect()
+}
+
+/// Maps a slice of rows, such as the result of `client.query`, into [`FromRow`] types.
+///
+/// ```rust, no_run
+/// use tokio_postgres_utils::{TryFromRow, TryMapRows};
+///
+/// #[derive(TryFromRow)]
+/// struct User {
+///     id: i32,
+///     name: String,
+/// }
+///
+/// # async fn example(client: &tokio_postgres::Client) -> Result<(), tokio_postgres_utils::BoxError> {
+/// let rows = client.query("SELECT * FROM (VALUES (1, 'nur'), (2, 'ada')) AS t(id, name)", &[]).await?;
+/// let users = rows.try_map_collect::<User>()?;
+/// assert_eq!(users.iter().map(|user| (user.id, user.name.as_str())).collect::<Vec<_>>(), [(1, "nur"), (2, "ada")]);
+///
+/// let rows = client.query("SELECT * FROM (VALUES (1), (NULL)) AS t(id)", &[]).await?;
+/// assert!(rows.try_map_collect::<User>().is_err());
+/// # Ok(())
+/// # }
+/// ```
+pub trait TryMapRows {
+    /// Maps every row into `T`, stopping at the first row that fails.
+    fn try_map_collect<T: FromRow>(&self) -> Result<Vec<T>, BoxError>;
+}
+
+impl TryMapRows for [Row] {
+    fn try_map_collect<T: FromRow>(&self) -> Result<Vec<T>, BoxError> {
+        self.iter().map(T::from_row).collect()
+    }
+}
+
+/// Reads the `key_column` and `value_column` of every row into a map, e.g. from a `key`/`value` settings table.
+///
+/// When several rows share a key, the last one wins.
+///
+/// ```rust, no_run
+/// use std::collections::HashMap;
+/// use tokio_postgres_utils::rows_to_map;
+///
+/// # async fn example(client: &tokio_postgres::Client) -> Result<(), tokio_postgres_utils::BoxError> {
+/// let rows = client
+///     .query("SELECT * FROM (VALUES ('theme', 1), ('lang', 2), ('theme', 3)) AS t(name, value)", &[])
+///     .await?;
+/// let settings: HashMap<String, i32> = rows_to_map(&rows, "name", "value")?;
+/// assert_eq!(settings, HashMap::from([("theme".to_owned(), 3), ("lang".to_owned(), 2)]));
+/// # Ok(())
+/// # }
+/// ```
+pub fn rows_to_map<K, V>(rows: &[Row], key_column: &str, value_column: &str) -> Result<HashMap<K, V>, tokio_postgres::Error>
+where
+    K: FromSqlOwned + Eq + Hash,
+    V: FromSqlOwned,
+{
+    rows.iter()
+        .map(|row| Ok((row.try_get(key_column)?, row.try_get(value_column)?)))
+        .collect()
+}
+
+/// The position of a column in a row, counting from 0, as taken by `#[column(ordinal = RowIndex(..))]`.
+///
+/// ```rust, no_run
+/// use tokio_postgres_utils::RowIndex;
+///
+/// # async fn example(client: &tokio_postgres::Client) -> Result<(), tokio_postgres::Error> {
+/// let row = client.query_one("SELECT 1, 'Ada'", &[]).await?;
+/// let name: String = RowIndex(1).try_get(&row)?;
+/// assert_eq!(name, "Ada");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RowIndex(pub usize);
+
+impl RowIndex {
+    /// The position, as passed to `Row::get`.
+    pub const fn get(self) -> usize {
+        self.0
+    }
+
+    /// Decodes the column at this position of `row`.
+    pub fn try_get<'a, T: FromSql<'a>>(self, row: &'a Row) -> Result<T, tokio_postgres::Error> {
+        row.try_get(self.0)
+    }
+}
+
+impl From<usize> for RowIndex {
+    fn from(index: usize) -> Self {
+        RowIndex(index)
+    }
+}
+
+impl From<RowIndex> for usize {
+    fn from(index: RowIndex) -> Self {
+        index.0
+    }
+}
+
+impl fmt::Display for RowIndex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
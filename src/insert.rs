@@ -0,0 +1,176 @@
+use tokio_postgres::{
+    types::{ToSql, Type},
+    GenericClient, Statement,
+};
+
+/// Writes a struct as a table row, implemented by `#[derive(Insert)]`.
+///
+/// ## Example
+///
+/// ```rust
+/// use tokio_postgres_utils::Insert;
+///
+/// #[derive(Insert)]
+/// struct NewUser {
+///     name: String,
+///     #[column(rename = "mail")]
+///     email: String,
+///     #[column(skip)]
+///     confirmed: bool,
+/// }
+///
+/// assert_eq!(NewUser::COLUMNS, ["name", "mail"]);
+/// assert_eq!(NewUser::insert_sql("users"), "INSERT INTO users (name, mail) VALUES ($1, $2)");
+///
+/// let user = NewUser { name: "Nur".into(), email: "nur@example.com".into(), confirmed: false };
+/// assert_eq!(user.as_params().len(), 2);
+/// ```
+pub trait Insert {
+    /// Column names written, in the same order as [`Insert::as_params`].
+    const COLUMNS: &'static [&'static str];
+
+    /// The Postgres type of each param, `None` where the field type has no known mapping.
+    ///
+    /// `#[derive(Insert)]` maps the common scalar types, their `Option`s and their arrays, e.g. `i32` to `INT4`,
+    /// `String` and `&str` to `TEXT` or `Vec<u8>` to `BYTEA`. Used by [`prepare_insert`].
+    const PARAM_TYPES: &'static [Option<Type>] = &[];
+
+    /// Returns the field values, one per column.
+    fn as_params(&self) -> Vec<&(dyn ToSql + Sync)>;
+
+    /// The column list of generated statements, [`Insert::COLUMNS`] separated by commas.
+    ///
+    /// With `#[column(quote_identifiers)]`, each column is double-quoted.
+    fn column_list() -> String {
+        Self::COLUMNS.join(", ")
+    }
+
+    /// Builds an `INSERT` statement for a single row, with `$n` placeholders for [`Insert::as_params`].
+    fn insert_sql(table: &str) -> String {
+        if Self::COLUMNS.is_empty() {
+            return format!("INSERT INTO {table} DEFAULT VALUES");
+        }
+        let placeholders = (1..=Self::COLUMNS.len())
+            .map(|i| format!("${i}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!(
+            "INSERT INTO {table} ({}) VALUES ({placeholders})",
+            Self::column_list()
+        )
+    }
+}
+
+impl<T: Insert + ?Sized> Insert for &T {
+    const COLUMNS: &'static [&'static str] = T::COLUMNS;
+    const PARAM_TYPES: &'static [Option<Type>] = T::PARAM_TYPES;
+
+    #[inline]
+    fn as_params(&self) -> Vec<&(dyn ToSql + Sync)> {
+        T::as_params(self)
+    }
+
+    #[inline]
+    fn column_list() -> String {
+        T::column_list()
+    }
+}
+
+/// Prepares the [`Insert::insert_sql`] statement of `T`, with the param types of [`Insert::PARAM_TYPES`].
+///
+/// Typed params spare the server from inferring them from the columns, and let e.g. an `i32` field be inserted into a `bigint` column.
+/// Types are passed up to the first field without a known mapping, the server infers the remaining ones as with an untyped `prepare`.
+///
+/// ```rust, no_run
+/// use tokio_postgres_utils::{prepare_insert, Insert};
+///
+/// #[derive(Insert)]
+/// struct NewUser {
+///     id: i32,
+///     name: String,
+///     tags: Vec<String>,
+/// }
+///
+/// # async fn example(client: &tokio_postgres::Client) -> Result<(), tokio_postgres::Error> {
+/// client.batch_execute("CREATE TEMP TABLE users (id int8, name text, tags text[])").await?;
+///
+/// let stmt = prepare_insert::<NewUser>(client, "users").await?;
+/// assert_eq!(stmt.params(), [tokio_postgres::types::Type::INT4, tokio_postgres::types::Type::TEXT, tokio_postgres::types::Type::TEXT_ARRAY]);
+///
+/// let user = NewUser { id: 1, name: "Nur".into(), tags: vec!["admin".into()] };
+/// client.execute(&stmt, &user.as_params()).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn prepare_insert<T: Insert + ?Sized>(client: &impl GenericClient, table: &str) -> Result<Statement, tokio_postgres::Error> {
+    let types: Vec<Type> = T::PARAM_TYPES.iter().map_while(Clone::clone).collect();
+    client.prepare_typed(&T::insert_sql(table), &types).await
+}
+
+/// The most params a single statement can bind, as `tokio_postgres` sends their count as an `i16`.
+const MAX_PARAMS: usize = i16::MAX as usize;
+
+/// Inserts `rows` into `table` with multi-row `INSERT ... VALUES ($1, $2), ($3, $4), ..` statements, and returns the number of rows inserted.
+///
+/// Rows are split over as many statements as needed to stay below the limit of 32767 params per statement.
+/// Each statement is prepared with the param types of [`Insert::PARAM_TYPES`] when they're all known, as in [`prepare_insert`].
+/// Statements run one after the other, so run them in a transaction to insert all rows or none.
+/// For large batches, [`copy_in_as`](crate::copy_in_as) is faster.
+///
+/// ## Example
+///
+/// ```rust, no_run
+/// use tokio_postgres_utils::{insert_many, Insert};
+///
+/// #[derive(Insert)]
+/// struct NewUser {
+///     id: i32,
+///     name: String,
+/// }
+///
+/// # async fn example(client: &tokio_postgres::Client) -> Result<(), tokio_postgres::Error> {
+/// client.batch_execute("CREATE TEMP TABLE users (id int4, name text)").await?;
+///
+/// let users: Vec<NewUser> = (0..40_000).map(|id| NewUser { id, name: format!("user {id}") }).collect();
+/// assert_eq!(insert_many(client, "users", &users).await?, 40_000);
+///
+/// let count: i64 = client.query_one("SELECT count(*) FROM users", &[]).await?.get(0);
+/// assert_eq!(count, 40_000);
+/// # Ok(())
+/// # }
+/// ```
+pub async fn insert_many<T: Insert>(client: &impl GenericClient, table: &str, rows: &[T]) -> Result<u64, tokio_postgres::Error> {
+    let width = T::COLUMNS.len();
+    if width == 0 {
+        let mut inserted = 0;
+        for _ in rows {
+            inserted += client.execute(&T::insert_sql(table), &[]).await?;
+        }
+        return Ok(inserted);
+    }
+    let row_types: Vec<Type> = T::PARAM_TYPES.iter().map_while(Clone::clone).collect();
+    let mut inserted = 0;
+    for chunk in rows.chunks(MAX_PARAMS / width) {
+        let values = (0..chunk.len())
+            .map(|row| {
+                let placeholders = (1..=width)
+                    .map(|i| format!("${}", row * width + i))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!("({placeholders})")
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        let sql = format!("INSERT INTO {table} ({}) VALUES {values}", T::column_list());
+        // Past the first row, params can only be typed if every column's type is known.
+        let types: Vec<Type> = if row_types.len() == width {
+            row_types.iter().cycle().take(width * chunk.len()).cloned().collect()
+        } else {
+            row_types.clone()
+        };
+        let stmt = client.prepare_typed(&sql, &types).await?;
+        let params: Vec<&(dyn ToSql + Sync)> = chunk.iter().flat_map(Insert::as_params).collect();
+        inserted += client.execute(&stmt, &params).await?;
+    }
+    Ok(inserted)
+}
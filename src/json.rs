@@ -0,0 +1,123 @@
+use crate::{datetime, BoxError, __private::JsonColumn};
+use serde_json::{Map, Value};
+use tokio_postgres::{
+    types::{FromSql, Kind, Type},
+    Row,
+};
+
+/// Converts every column of `row` into a JSON object keyed by column name, e.g. to echo the result of an arbitrary query.
+///
+/// Booleans, integers and floats become JSON numbers and booleans, with `NaN` and infinite floats written as `null`.
+/// Text columns become strings, `json` and `jsonb` columns are kept as is, and `bytea` is written as a hex string like `\x0a0b`.
+/// `date`, `time`, `timestamp` and `timestamptz` become RFC 3339 strings like `2000-01-02T03:04:05Z`, infinite ones `infinity` or `-infinity`.
+/// With the `decimal` and `uuid` features, `numeric` and `uuid` become strings. One dimensional arrays of these become arrays,
+/// and `NULL` becomes `null`.
+///
+/// Fails on the first column of another type. With several columns of the same name, the last one is kept.
+///
+/// ## Example
+///
+/// ```rust, no_run
+/// use serde_json::json;
+/// use tokio_postgres_utils::row_to_json;
+///
+/// # async fn example(client: &tokio_postgres::Client) -> Result<(), tokio_postgres_utils::BoxError> {
+/// let row = client
+///     .query_one(
+///         r#"SELECT 1 AS id, 'nur' AS name, NULL::text AS email, 1.5::float8 AS score,
+///                   ARRAY[1, NULL] AS ids, '{"admin": true}'::jsonb AS flags, '\x0a0b'::bytea AS avatar"#,
+///         &[],
+///     )
+///     .await?;
+/// assert_eq!(
+///     row_to_json(&row)?,
+///     json!({
+///         "id": 1,
+///         "name": "nur",
+///         "email": null,
+///         "score": 1.5,
+///         "ids": [1, null],
+///         "flags": { "admin": true },
+///         "avatar": "\\x0a0b",
+///     })
+/// );
+///
+/// let row = client.query_one("SELECT '2000-01-02 03:04:05+00'::timestamptz AS created_at", &[]).await?;
+/// assert_eq!(row_to_json(&row)?, json!({ "created_at": "2000-01-02T03:04:05Z" }));
+///
+/// let row = client.query_one("SELECT '1 day'::interval AS ttl", &[]).await?;
+/// assert!(row_to_json(&row).is_err());
+/// # Ok(())
+/// # }
+/// ```
+pub fn row_to_json(row: &Row) -> Result<Value, tokio_postgres::Error> {
+    let mut object = Map::new();
+    for (i, column) in row.columns().iter().enumerate() {
+        let JsonValue(value) = row.try_get(i)?;
+        object.insert(column.name().to_owned(), value);
+    }
+    Ok(Value::Object(object))
+}
+
+/// A column of any type [`row_to_json`] supports, converted to JSON.
+struct JsonValue(Value);
+
+impl<'a> FromSql<'a> for JsonValue {
+    fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<Self, BoxError> {
+        if let Kind::Array(_) = ty.kind() {
+            let values = Vec::<JsonValue>::from_sql(ty, raw)?;
+            return Ok(JsonValue(values.into_iter().map(|value| value.0).collect()));
+        }
+        let value = match *ty {
+            Type::BOOL => bool::from_sql(ty, raw)?.into(),
+            Type::CHAR => i8::from_sql(ty, raw)?.into(),
+            Type::INT2 => i16::from_sql(ty, raw)?.into(),
+            Type::INT4 => i32::from_sql(ty, raw)?.into(),
+            Type::INT8 => i64::from_sql(ty, raw)?.into(),
+            Type::OID => u32::from_sql(ty, raw)?.into(),
+            Type::FLOAT4 => f32::from_sql(ty, raw)?.into(),
+            Type::FLOAT8 => f64::from_sql(ty, raw)?.into(),
+            Type::JSON | Type::JSONB => serde_json::from_slice(JsonColumn::from_sql(ty, raw)?.0)?,
+            _ if datetime::accepts(ty) => datetime::format(ty, raw)?.into(),
+            Type::BYTEA => {
+                let hex = raw.iter().map(|byte| format!("{byte:02x}")).collect::<String>();
+                format!("\\x{hex}").into()
+            }
+            #[cfg(feature = "decimal")]
+            Type::NUMERIC => rust_decimal::Decimal::from_sql(ty, raw)?.to_string().into(),
+            #[cfg(feature = "uuid")]
+            Type::UUID => uuid::Uuid::from_sql(ty, raw)?.to_string().into(),
+            _ => String::from_sql(ty, raw)?.into(),
+        };
+        Ok(JsonValue(value))
+    }
+
+    fn from_sql_null(_: &Type) -> Result<Self, BoxError> {
+        Ok(JsonValue(Value::Null))
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        match ty.kind() {
+            Kind::Array(member) => Self::accepts(member),
+            _ => {
+                matches!(
+                    *ty,
+                    Type::BOOL
+                        | Type::CHAR
+                        | Type::INT2
+                        | Type::INT4
+                        | Type::INT8
+                        | Type::OID
+                        | Type::FLOAT4
+                        | Type::FLOAT8
+                        | Type::JSON
+                        | Type::JSONB
+                        | Type::BYTEA
+                ) || (cfg!(feature = "decimal") && *ty == Type::NUMERIC)
+                    || (cfg!(feature = "uuid") && *ty == Type::UUID)
+                    || datetime::accepts(ty)
+                    || <String as FromSql>::accepts(ty)
+            }
+        }
+    }
+}
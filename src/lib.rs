@@ -1,4 +1,43 @@
 #![warn(missing_docs)]
 #![doc = include_str!("../README.md")]
 
+mod batch;
+mod composite;
+mod copy;
+mod cursor;
+mod datetime;
+mod error;
+mod from_row;
+mod insert;
+mod order;
+mod pairs;
+mod query;
+mod row_access;
+mod schema;
+mod stream;
+
+#[doc(hidden)]
+pub mod __private;
+
+#[cfg(feature = "sqlx-compat")]
+pub mod sqlx_compat;
+
+#[cfg(feature = "json")]
+mod json;
+
+pub use batch::*;
+pub use composite::*;
+pub use copy::*;
+pub use cursor::*;
+pub use error::*;
+pub use from_row::*;
+pub use insert::*;
+#[cfg(feature = "json")]
+pub use json::*;
+pub use order::*;
+pub use pairs::*;
+pub use query::*;
+pub use row_access::*;
+pub use schema::*;
+pub use stream::*;
 pub use tokio_postgres_macros::*;
@@ -0,0 +1,96 @@
+use std::{fmt, marker::PhantomData};
+
+/// A column read by a field of `T`, generated as a `COL_<FIELD>` constant by `#[derive(FromRow)]` and `#[derive(TryFromRow)]`.
+///
+/// Keys can only be built by the derives, so the `order_by` function of a struct only ever writes the names of its columns.
+///
+/// ## Example
+///
+/// ```rust
+/// use tokio_postgres_utils::{FromRow, SortOrder};
+///
+/// #[derive(FromRow)]
+/// struct User {
+///     id: i32,
+///     #[column(rename = "display_name")]
+///     name: String,
+/// }
+///
+/// assert_eq!(User::COL_NAME.name(), "display_name");
+/// assert_eq!(
+///     User::order_by(&[(User::COL_NAME, SortOrder::Asc), (User::COL_ID, SortOrder::Desc)]),
+///     "ORDER BY display_name ASC, id DESC"
+/// );
+/// assert_eq!(User::order_by(&[]), "");
+/// ```
+///
+/// Keys of other structs are rejected:
+///
+/// ```rust, compile_fail
+/// use tokio_postgres_utils::{FromRow, SortOrder};
+///
+/// #[derive(FromRow)]
+/// struct User {
+///     id: i32,
+/// }
+///
+/// #[derive(FromRow)]
+/// struct Post {
+///     id: i32,
+/// }
+///
+/// User::order_by(&[(Post::COL_ID, SortOrder::Asc)]);
+/// ```
+pub struct ColumnKey<T: ?Sized> {
+    pub(crate) name: &'static str,
+    pub(crate) _marker: PhantomData<fn() -> T>,
+}
+
+impl<T: ?Sized> ColumnKey<T> {
+    /// The column name, as read from the row.
+    pub const fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+impl<T: ?Sized> Clone for ColumnKey<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: ?Sized> Copy for ColumnKey<T> {}
+
+impl<T: ?Sized> PartialEq for ColumnKey<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+    }
+}
+
+impl<T: ?Sized> Eq for ColumnKey<T> {}
+
+impl<T: ?Sized> fmt::Debug for ColumnKey<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ColumnKey").field(&self.name).finish()
+    }
+}
+
+/// The direction of a column in an `ORDER BY` clause.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SortOrder {
+    /// Smallest first, written as `ASC`.
+    #[default]
+    Asc,
+    /// Largest first, written as `DESC`.
+    Desc,
+}
+
+impl SortOrder {
+    /// The SQL keyword of the direction.
+    pub const fn as_sql(self) -> &'static str {
+        match self {
+            SortOrder::Asc => "ASC",
+            SortOrder::Desc => "DESC",
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use crate::{datetime, BoxError, __private::JsonColumn};
+use std::{fmt::Write, net::IpAddr};
+use tokio_postgres::{
+    types::{FromSql, Kind, Type},
+    Row,
+};
+
+/// Lists the columns of `row` in order, each with its value as text, e.g. to log a row while debugging.
+///
+/// Booleans, numbers, text and `inet` are written as they display in Rust, `json` and `jsonb` as their text,
+/// `date`, `time`, `timestamp` and `timestamptz` in the RFC 3339 format like `2000-01-02T03:04:05Z`, and `bytea` as a hex string like `\x0a0b`.
+/// With the `decimal` and `uuid` features, `numeric` and `uuid` are written as they display too.
+/// One dimensional arrays of these are written like `{1,NULL}`, and `NULL` as `NULL`.
+/// Other types, or values that fail to decode, are written as the type name and their raw bytes in hex, like `interval(\x0000)`.
+///
+/// ## Example
+///
+/// ```rust, no_run
+/// use tokio_postgres_utils::row_to_pairs;
+///
+/// # async fn example(client: &tokio_postgres::Client) -> Result<(), tokio_postgres_utils::BoxError> {
+/// let row = client
+///     .query_one(
+///         r#"SELECT 1 AS id, 'nur' AS name, NULL::text AS email, ARRAY[1, NULL] AS ids, '\x0a0b'::bytea AS avatar, '2000-01-02'::date AS joined"#,
+///         &[],
+///     )
+///     .await?;
+/// let pairs = row_to_pairs(&row);
+/// let pairs: Vec<(&str, &str)> = pairs.iter().map(|(column, value)| (column.as_str(), value.as_str())).collect();
+/// assert_eq!(
+///     pairs,
+///     [
+///         ("id", "1"),
+///         ("name", "nur"),
+///         ("email", "NULL"),
+///         ("ids", "{1,NULL}"),
+///         ("avatar", "\\x0a0b"),
+///         ("joined", "2000-01-02"),
+///     ]
+/// );
+/// # Ok(())
+/// # }
+/// ```
+pub fn row_to_pairs(row: &Row) -> Vec<(String, String)> {
+    row.columns()
+        .iter()
+        .enumerate()
+        .map(|(i, column)| {
+            let value = row.try_get(i).map_or_else(|err| err.to_string(), |TextValue(value)| value);
+            (column.name().to_owned(), value)
+        })
+        .collect()
+}
+
+/// A column of any type, written as text by [`row_to_pairs`].
+struct TextValue(String);
+
+impl TextValue {
+    fn decode(ty: &Type, raw: &[u8]) -> Result<String, BoxError> {
+        if let Kind::Array(_) = ty.kind() {
+            let values = Vec::<TextValue>::from_sql(ty, raw)?;
+            let values = values.into_iter().map(|value| value.0).collect::<Vec<_>>();
+            return Ok(format!("{{{}}}", values.join(",")));
+        }
+        Ok(match *ty {
+            Type::BOOL => bool::from_sql(ty, raw)?.to_string(),
+            Type::CHAR => i8::from_sql(ty, raw)?.to_string(),
+            Type::INT2 => i16::from_sql(ty, raw)?.to_string(),
+            Type::INT4 => i32::from_sql(ty, raw)?.to_string(),
+            Type::INT8 => i64::from_sql(ty, raw)?.to_string(),
+            Type::OID => u32::from_sql(ty, raw)?.to_string(),
+            Type::FLOAT4 => f32::from_sql(ty, raw)?.to_string(),
+            Type::FLOAT8 => f64::from_sql(ty, raw)?.to_string(),
+            Type::JSON | Type::JSONB => std::str::from_utf8(JsonColumn::from_sql(ty, raw)?.0)?.to_owned(),
+            Type::BYTEA => hex(raw),
+            Type::INET => IpAddr::from_sql(ty, raw)?.to_string(),
+            #[cfg(feature = "decimal")]
+            Type::NUMERIC => rust_decimal::Decimal::from_sql(ty, raw)?.to_string(),
+            #[cfg(feature = "uuid")]
+            Type::UUID => uuid::Uuid::from_sql(ty, raw)?.to_string(),
+            _ if datetime::accepts(ty) => datetime::format(ty, raw)?,
+            _ if <String as FromSql>::accepts(ty) => String::from_sql(ty, raw)?,
+            _ => return Err("unsupported type".into()),
+        })
+    }
+}
+
+/// Writes `raw` as a hex string like `\x0a0b`, as Postgres writes `bytea`.
+fn hex(raw: &[u8]) -> String {
+    raw.iter().fold("\\x".to_owned(), |mut hex, byte| {
+        let _ = write!(hex, "{byte:02x}");
+        hex
+    })
+}
+
+impl<'a> FromSql<'a> for TextValue {
+    fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<Self, BoxError> {
+        let value = TextValue::decode(ty, raw).unwrap_or_else(|_| format!("{ty}({})", hex(raw)));
+        Ok(TextValue(value))
+    }
+
+    fn from_sql_null(_: &Type) -> Result<Self, BoxError> {
+        Ok(TextValue("NULL".to_owned()))
+    }
+
+    fn accepts(_: &Type) -> bool {
+        true
+    }
+}
@@ -0,0 +1,296 @@
+use crate::FromRow;
+use std::{error::Error, future::Future};
+use tokio_postgres::{types::ToSql, GenericClient, Row, ToStatement};
+
+/// Error returned by the query helpers, covering both database and decode failures.
+pub type BoxError = Box<dyn Error + Send + Sync>;
+
+/// A list of query parameters.
+///
+/// Implemented for tuples (up to arity 12), `Vec<T>` and arrays of [`ToSql`] values,
+/// so parameters can be passed without building a `&[&(dyn ToSql + Sync)]` by hand.
+/// The slice form is still accepted.
+pub trait IntoParams {
+    /// Returns the parameters as a list of trait objects, in order.
+    fn to_params(&self) -> Vec<&(dyn ToSql + Sync)>;
+}
+
+impl IntoParams for &[&(dyn ToSql + Sync)] {
+    #[inline]
+    fn to_params(&self) -> Vec<&(dyn ToSql + Sync)> {
+        self.to_vec()
+    }
+}
+
+impl<const N: usize> IntoParams for &[&(dyn ToSql + Sync); N] {
+    #[inline]
+    fn to_params(&self) -> Vec<&(dyn ToSql + Sync)> {
+        self.to_vec()
+    }
+}
+
+impl<T: ToSql + Sync> IntoParams for Vec<T> {
+    #[inline]
+    fn to_params(&self) -> Vec<&(dyn ToSql + Sync)> {
+        self.iter().map(|v| v as &(dyn ToSql + Sync)).collect()
+    }
+}
+
+impl<T: ToSql + Sync, const N: usize> IntoParams for [T; N] {
+    #[inline]
+    fn to_params(&self) -> Vec<&(dyn ToSql + Sync)> {
+        self.iter().map(|v| v as &(dyn ToSql + Sync)).collect()
+    }
+}
+
+macro_rules! impl_into_params_for_tuple {
+    [$($name:ident : $idx:tt),*] => {
+        impl<$($name: ToSql + Sync),*> IntoParams for ($($name,)*) {
+            #[inline]
+            fn to_params(&self) -> Vec<&(dyn ToSql + Sync)> {
+                vec![$(&self.$idx as &(dyn ToSql + Sync)),*]
+            }
+        }
+    };
+}
+
+impl IntoParams for () {
+    #[inline]
+    fn to_params(&self) -> Vec<&(dyn ToSql + Sync)> {
+        Vec::new()
+    }
+}
+
+impl_into_params_for_tuple!(T0: 0);
+impl_into_params_for_tuple!(T0: 0, T1: 1);
+impl_into_params_for_tuple!(T0: 0, T1: 1, T2: 2);
+impl_into_params_for_tuple!(T0: 0, T1: 1, T2: 2, T3: 3);
+impl_into_params_for_tuple!(T0: 0, T1: 1, T2: 2, T3: 3, T4: 4);
+impl_into_params_for_tuple!(T0: 0, T1: 1, T2: 2, T3: 3, T4: 4, T5: 5);
+impl_into_params_for_tuple!(T0: 0, T1: 1, T2: 2, T3: 3, T4: 4, T5: 5, T6: 6);
+impl_into_params_for_tuple!(T0: 0, T1: 1, T2: 2, T3: 3, T4: 4, T5: 5, T6: 6, T7: 7);
+impl_into_params_for_tuple!(T0: 0, T1: 1, T2: 2, T3: 3, T4: 4, T5: 5, T6: 6, T7: 7, T8: 8);
+impl_into_params_for_tuple!(T0: 0, T1: 1, T2: 2, T3: 3, T4: 4, T5: 5, T6: 6, T7: 7, T8: 8, T9: 9);
+impl_into_params_for_tuple!(T0: 0, T1: 1, T2: 2, T3: 3, T4: 4, T5: 5, T6: 6, T7: 7, T8: 8, T9: 9, T10: 10);
+impl_into_params_for_tuple!(T0: 0, T1: 1, T2: 2, T3: 3, T4: 4, T5: 5, T6: 6, T7: 7, T8: 8, T9: 9, T10: 10, T11: 11);
+
+/// Extension methods for [`GenericClient`] (`Client` and `Transaction`), mapping rows into structs.
+///
+/// ## Example
+///
+/// ```rust, no_run
+/// use tokio_postgres_utils::{ClientExt, FromRow};
+///
+/// #[derive(FromRow)]
+/// struct User {
+///     id: i32,
+///     name: String,
+/// }
+///
+/// async fn active_users(client: &tokio_postgres::Client) -> Result<Vec<User>, tokio_postgres_utils::BoxError> {
+///     client
+///         .query_as::<User>(
+///             "SELECT id, name FROM users WHERE active = $1 AND age > $2 AND name <> $3",
+///             (true, 18, "admin"),
+///         )
+///         .await
+/// }
+/// ```
+///
+/// ## `Send` and cancellation
+///
+/// The returned futures are `Send`, so they can be spawned on a multi-threaded runtime or raced in `tokio::select!`.
+///
+/// They are cancellation-safe in the sense that dropping one never leaves the client in a broken state:
+/// rows that were already received are discarded, and the client can be used for the next query right away.
+/// The statement itself may still run to completion on the server, use [`Client::cancel_token`](tokio_postgres::Client::cancel_token) to stop it.
+///
+/// ```rust, no_run
+/// use std::sync::Arc;
+/// use tokio_postgres_utils::{ClientExt, FromRow};
+///
+/// #[derive(Debug, FromRow)]
+/// struct Sleep {
+///     slept: bool,
+/// }
+///
+/// async fn example(client: Arc<tokio_postgres::Client>) -> Result<(), tokio_postgres_utils::BoxError> {
+///     let task = tokio::spawn({
+///         let client = client.clone();
+///         async move { client.query_as::<Sleep>("SELECT pg_sleep(10) IS NULL AS slept", ()).await }
+///     });
+///     task.abort();
+///     assert!(task.await.unwrap_err().is_cancelled());
+///
+///     let rows = client.query_as::<Sleep>("SELECT true AS slept", ()).await?;
+///     assert!(rows[0].slept);
+///     Ok(())
+/// }
+/// ```
+pub trait ClientExt {
+    /// Executes a statement, mapping each resulting row into `T`.
+    ///
+    /// `T` can be any type implementing [`FromRow`], which includes both the `FromRow`
+    /// and [`TryFromRow`](crate::TryFromRow) derives, as well as tuples of them, e.g. for joins.
+    fn query_as<T>(
+        &self,
+        statement: &(impl ToStatement + Sync + Send + ?Sized),
+        params: impl IntoParams + Send,
+    ) -> impl Future<Output = Result<Vec<T>, BoxError>> + Send
+    where
+        T: FromRow;
+
+    /// Like [`ClientExt::query_as`], but also returns each original row alongside its mapped value,
+    /// so columns that aren't part of `T` can still be read without a second query.
+    ///
+    /// ```rust, no_run
+    /// # use tokio_postgres_utils::{ClientExt, FromRow};
+    /// #[derive(FromRow)]
+    /// struct User {
+    ///     id: i32,
+    /// }
+    ///
+    /// # async fn example(client: &tokio_postgres::Client) -> Result<(), tokio_postgres_utils::BoxError> {
+    /// let rows = client.query_as_with_row::<User>("SELECT 1 AS id, 'ada' AS login", ()).await?;
+    /// let (user, row) = &rows[0];
+    /// assert_eq!(user.id, 1);
+    /// assert_eq!(row.get::<_, &str>("login"), "ada");
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn query_as_with_row<T>(
+        &self,
+        statement: &(impl ToStatement + Sync + Send + ?Sized),
+        params: impl IntoParams + Send,
+    ) -> impl Future<Output = Result<Vec<(T, Row)>, BoxError>> + Send
+    where
+        T: FromRow;
+
+    /// Like [`ClientExt::query_as`], but passes each mapped row through an async `then` step,
+    /// e.g. to resolve a reference with another query. Rows are processed one after another, in order.
+    ///
+    /// ```rust, no_run
+    /// # use tokio_postgres_utils::{BoxError, ClientExt, FromRow};
+    /// #[derive(FromRow)]
+    /// struct Post {
+    ///     title: String,
+    ///     author_id: i32,
+    ///     #[column(skip)]
+    ///     author: String,
+    /// }
+    ///
+    /// # async fn example(client: &tokio_postgres::Client) -> Result<(), BoxError> {
+    /// let posts: Vec<Post> = client
+    ///     .query_as_then("SELECT title, author_id FROM posts", (), |mut post: Post| async move {
+    ///         let row = client.query_one("SELECT name FROM users WHERE id = $1", &[&post.author_id]).await?;
+    ///         post.author = row.get("name");
+    ///         Ok::<_, BoxError>(post)
+    ///     })
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn query_as_then<T, U, E, Fut>(
+        &self,
+        statement: &(impl ToStatement + Sync + Send + ?Sized),
+        params: impl IntoParams + Send,
+        then: impl FnMut(T) -> Fut + Send,
+    ) -> impl Future<Output = Result<Vec<U>, BoxError>> + Send
+    where
+        T: FromRow,
+        U: Send,
+        Fut: Future<Output = Result<U, E>> + Send,
+        E: Into<BoxError>;
+
+    /// Executes a write statement with a `RETURNING` clause, mapping the returned row into `T`,
+    /// or `None` if the statement affected no row. Errors if it returned more than one, though the statement still ran.
+    ///
+    /// ```rust, no_run
+    /// # use tokio_postgres_utils::{ClientExt, FromRow};
+    /// #[derive(FromRow)]
+    /// struct Renamed {
+    ///     id: i32,
+    ///     name: String,
+    /// }
+    ///
+    /// # async fn example(client: &tokio_postgres::Client) -> Result<(), tokio_postgres_utils::BoxError> {
+    /// client.batch_execute("CREATE TEMP TABLE users (id int4, name text); INSERT INTO users VALUES (1, 'nur')").await?;
+    /// let sql = "UPDATE users SET name = $1 WHERE id = $2 RETURNING id, name";
+    ///
+    /// let user = client.execute_returning_as::<Renamed>(sql, ("ann", 1)).await?.unwrap();
+    /// assert_eq!((user.id, user.name.as_str()), (1, "ann"));
+    /// assert!(client.execute_returning_as::<Renamed>(sql, ("ann", 2)).await?.is_none());
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn execute_returning_as<T>(
+        &self,
+        statement: &(impl ToStatement + Sync + Send + ?Sized),
+        params: impl IntoParams + Send,
+    ) -> impl Future<Output = Result<Option<T>, BoxError>> + Send
+    where
+        T: FromRow;
+}
+
+impl<C: GenericClient + Sync> ClientExt for C {
+    async fn query_as<T>(
+        &self,
+        statement: &(impl ToStatement + Sync + Send + ?Sized),
+        params: impl IntoParams + Send,
+    ) -> Result<Vec<T>, BoxError>
+    where
+        T: FromRow,
+    {
+        let rows = self.query(statement, &params.to_params()).await?;
+        rows.iter()
+            .map(T::from_row)
+            .collect()
+    }
+
+    async fn query_as_with_row<T>(
+        &self,
+        statement: &(impl ToStatement + Sync + Send + ?Sized),
+        params: impl IntoParams + Send,
+    ) -> Result<Vec<(T, Row)>, BoxError>
+    where
+        T: FromRow,
+    {
+        let rows = self.query(statement, &params.to_params()).await?;
+        rows.into_iter()
+            .map(|row| Ok((T::from_row(&row)?, row)))
+            .collect()
+    }
+
+    async fn query_as_then<T, U, E, Fut>(
+        &self,
+        statement: &(impl ToStatement + Sync + Send + ?Sized),
+        params: impl IntoParams + Send,
+        mut then: impl FnMut(T) -> Fut + Send,
+    ) -> Result<Vec<U>, BoxError>
+    where
+        T: FromRow,
+        U: Send,
+        Fut: Future<Output = Result<U, E>> + Send,
+        E: Into<BoxError>,
+    {
+        let rows = self.query(statement, &params.to_params()).await?;
+        let mut values = Vec::with_capacity(rows.len());
+        for row in &rows {
+            let value = T::from_row(row)?;
+            values.push(then(value).await.map_err(Into::into)?);
+        }
+        Ok(values)
+    }
+
+    async fn execute_returning_as<T>(
+        &self,
+        statement: &(impl ToStatement + Sync + Send + ?Sized),
+        params: impl IntoParams + Send,
+    ) -> Result<Option<T>, BoxError>
+    where
+        T: FromRow,
+    {
+        let row = self.query_opt(statement, &params.to_params()).await?;
+        row.as_ref().map(T::from_row).transpose()
+    }
+}
@@ -0,0 +1,83 @@
+use crate::BoxError;
+use tokio_postgres::{types::FromSql, Row};
+
+/// A source of column values read by name, such as a [`Row`], a cached map or a mock.
+///
+/// ## Example
+///
+/// A fake row holding binary encoded values, to test mappings without a database:
+///
+/// ```rust
+/// use std::collections::HashMap;
+/// use tokio_postgres::types::{FromSql, Type};
+/// use tokio_postgres_utils::{BoxError, FromRowAccess, RowAccess, TryFromRow};
+///
+/// struct FakeRow(HashMap<&'static str, (Type, Option<Vec<u8>>)>);
+///
+/// impl RowAccess for FakeRow {
+///     fn try_get<'a, T: FromSql<'a>>(&'a self, column: &str) -> Result<T, BoxError> {
+///         let (ty, value) = self.0.get(column).ok_or_else(|| format!("no column `{column}`"))?;
+///         if !T::accepts(ty) {
+///             return Err(format!("column `{column}` is not a {ty}").into());
+///         }
+///         T::from_sql_nullable(ty, value.as_deref())
+///     }
+/// }
+///
+/// #[derive(TryFromRow)]
+/// #[column(row_access)]
+/// struct User {
+///     id: i32,
+///     name: Option<String>,
+/// }
+///
+/// let row = FakeRow(HashMap::from([
+///     ("id", (Type::INT4, Some(7i32.to_be_bytes().to_vec()))),
+///     ("name", (Type::TEXT, None)),
+/// ]));
+/// let user = User::from_row_access(&row).unwrap();
+/// assert_eq!((user.id, user.name), (7, None));
+/// ```
+pub trait RowAccess {
+    /// Reads `column` as `T`.
+    fn try_get<'a, T: FromSql<'a>>(&'a self, column: &str) -> Result<T, BoxError>;
+
+    /// Reads `column` as `T`, panicking if it can't.
+    fn get<'a, T: FromSql<'a>>(&'a self, column: &str) -> T {
+        match self.try_get(column) {
+            Ok(value) => value,
+            Err(err) => panic!("error retrieving column {column}: {err}"),
+        }
+    }
+}
+
+impl RowAccess for Row {
+    #[inline]
+    fn try_get<'a, T: FromSql<'a>>(&'a self, column: &str) -> Result<T, BoxError> {
+        Row::try_get(self, column).map_err(Into::into)
+    }
+}
+
+/// Reads a struct from any [`RowAccess`], implemented by `#[derive(TryFromRow)]` with `#[column(row_access)]`.
+///
+/// As [`Row`] implements [`RowAccess`], the same struct can be read from real rows and from fakes:
+///
+/// ```rust, no_run
+/// use tokio_postgres_utils::{FromRowAccess, TryFromRow};
+///
+/// #[derive(TryFromRow)]
+/// #[column(row_access)]
+/// struct User {
+///     id: i32,
+/// }
+///
+/// # async fn example(client: &tokio_postgres::Client) -> Result<(), tokio_postgres_utils::BoxError> {
+/// let row = client.query_one("SELECT 7 AS id", &[]).await?;
+/// assert_eq!(User::from_row_access(&row)?.id, User::try_from(&row)?.id);
+/// # Ok(())
+/// # }
+/// ```
+pub trait FromRowAccess: Sized {
+    /// Reads `Self` from `row`.
+    fn from_row_access<R: RowAccess + ?Sized>(row: &R) -> Result<Self, BoxError>;
+}
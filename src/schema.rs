@@ -0,0 +1,251 @@
+use crate::FromRow;
+use std::fmt;
+use tokio_postgres::{types::Type, GenericClient};
+
+/// Checks that every column `T` reads exists in `table`, with a type its field accepts.
+///
+/// `table` is resolved like in a query, it may be schema qualified or found through the search path.
+/// When there is no such table, the report holds a single [`SchemaIssue::TableNotFound`].
+/// Columns of `flatten` fields are checked by validating the flattened type on its own.
+///
+/// Meant for startup checks and tests, to catch drift between a struct and the schema before the first query fails.
+///
+/// ## Example
+///
+/// ```rust, no_run
+/// use tokio_postgres_utils::{validate_struct_against, FromRow};
+///
+/// #[derive(FromRow)]
+/// struct User {
+///     id: i32,
+///     name: String,
+///     email: String,
+/// }
+///
+/// # async fn example(client: &tokio_postgres::Client) -> Result<(), tokio_postgres_utils::BoxError> {
+/// client.batch_execute("CREATE TEMP TABLE users (id int8, name text)").await?;
+///
+/// let report = validate_struct_against::<User>(client, "users").await?;
+/// assert!(!report.is_ok());
+/// assert_eq!(report.to_string(), "table `users` doesn't match: column `id` has type int8; column `email` is missing");
+/// # Ok(())
+/// # }
+/// ```
+pub async fn validate_struct_against<T: FromRow>(
+    client: &impl GenericClient,
+    table: &str,
+) -> Result<SchemaReport, tokio_postgres::Error> {
+    let found: bool = client.query_one("SELECT to_regclass($1) IS NOT NULL", &[&table]).await?.get(0);
+    if !found {
+        return Ok(SchemaReport {
+            table: table.to_owned(),
+            issues: vec![SchemaIssue::TableNotFound],
+        });
+    }
+    let rows = client
+        .query(
+            "SELECT c.column_name::text, t.oid \
+             FROM information_schema.columns c \
+             JOIN pg_namespace n ON n.nspname = c.udt_schema \
+             JOIN pg_type t ON t.typnamespace = n.oid AND t.typname = c.udt_name \
+             WHERE (c.table_schema, c.table_name) = ( \
+                 SELECT s.nspname::text, r.relname::text FROM pg_class r \
+                 JOIN pg_namespace s ON s.oid = r.relnamespace WHERE r.oid = to_regclass($1) \
+             )",
+            &[&table],
+        )
+        .await?;
+    let columns: Vec<(&str, u32)> = rows.iter().map(|row| (row.get(0), row.get(1))).collect();
+    Ok(compare::<T>(table, &columns))
+}
+
+/// Compares the columns `T` reads with the columns of `table`, given by name and type oid.
+///
+/// Columns of types unknown to `tokio_postgres`, such as enums, are only checked to exist.
+fn compare<T: FromRow>(table: &str, columns: &[(&str, u32)]) -> SchemaReport {
+    let issues = T::columns()
+        .iter()
+        .filter_map(|&column| {
+            let Some(&(_, oid)) = columns.iter().find(|(name, _)| *name == column) else {
+                return Some(SchemaIssue::MissingColumn { column });
+            };
+            let ty = Type::from_oid(oid)?;
+            match T::accepts_column(column, &ty) {
+                Some(false) => Some(SchemaIssue::TypeMismatch { column, found: ty }),
+                _ => None,
+            }
+        })
+        .collect();
+    SchemaReport {
+        table: table.to_owned(),
+        issues,
+    }
+}
+
+/// Fails the test if the columns a struct reads don't match a table, with a message listing every mismatch.
+///
+/// Runs [`validate_struct_against`] for `T`, so it must be awaited in an async test, and panics when the check fails,
+/// or when the table can't be queried. Meant for CI test suites, to catch drift between a struct and the migrations.
+///
+/// ## Example
+///
+/// ```rust, no_run
+/// use tokio_postgres_utils::{assert_struct_matches_schema, FromRow};
+///
+/// #[derive(FromRow)]
+/// struct User {
+///     id: i32,
+///     #[column(rename = "display_name")]
+///     name: String,
+/// }
+///
+/// #[derive(FromRow)]
+/// struct Admin {
+///     id: i64,
+///     email: String,
+/// }
+///
+/// # async fn example(client: &tokio_postgres::Client) -> Result<(), tokio_postgres_utils::BoxError> {
+/// client.batch_execute("CREATE TEMP TABLE users (id int4, display_name text)").await?;
+/// assert_struct_matches_schema!(User, client, "users");
+///
+/// // panics with "`Admin`: table `users` doesn't match: column `id` has type int4; column `email` is missing"
+/// assert_struct_matches_schema!(Admin, client, "users");
+/// # Ok(())
+/// # }
+/// ```
+#[macro_export]
+macro_rules! assert_struct_matches_schema {
+    ($ty:ty, $client:expr, $table:expr $(,)?) => {
+        match $crate::validate_struct_against::<$ty>($client, $table).await {
+            ::std::result::Result::Ok(report) => {
+                ::std::assert!(report.is_ok(), "`{}`: {}", ::std::stringify!($ty), report);
+            }
+            ::std::result::Result::Err(err) => {
+                ::std::panic!("`{}`: failed to read the columns of table `{}`: {}", ::std::stringify!($ty), $table, err);
+            }
+        }
+    };
+}
+
+/// The result of [`validate_struct_against`].
+#[derive(Debug)]
+pub struct SchemaReport {
+    table: String,
+    issues: Vec<SchemaIssue>,
+}
+
+impl SchemaReport {
+    /// Whether the struct matches the table.
+    pub fn is_ok(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// The mismatches found, in the order of `FromRow::columns()`.
+    pub fn issues(&self) -> &[SchemaIssue] {
+        &self.issues
+    }
+}
+
+impl fmt::Display for SchemaReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_ok() {
+            return write!(f, "table `{}` matches", self.table);
+        }
+        if let [SchemaIssue::TableNotFound] = self.issues[..] {
+            return write!(f, "table `{}` not found", self.table);
+        }
+        write!(f, "table `{}` doesn't match", self.table)?;
+        for (i, issue) in self.issues.iter().enumerate() {
+            let sep = if i == 0 { ": " } else { "; " };
+            write!(f, "{sep}{issue}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A mismatch between a struct and its table.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SchemaIssue {
+    /// There is no such table, so none of its columns could be checked.
+    TableNotFound,
+    /// The table has no such column.
+    MissingColumn {
+        /// The column read.
+        column: &'static str,
+    },
+    /// The column has a type the field doesn't accept.
+    TypeMismatch {
+        /// The column read.
+        column: &'static str,
+        /// The type of the column in the table.
+        found: Type,
+    },
+}
+
+impl fmt::Display for SchemaIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SchemaIssue::TableNotFound => write!(f, "the table is not found"),
+            SchemaIssue::MissingColumn { column } => write!(f, "column `{column}` is missing"),
+            SchemaIssue::TypeMismatch { column, found } => write!(f, "column `{column}` has type {found}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compare, SchemaIssue};
+    use crate::{BoxError, FromRow};
+    use tokio_postgres::{types::Type, Row};
+
+    /// Reads `id` as an `int4`, `name` as any type and `email` as `text`.
+    struct User;
+
+    impl FromRow for User {
+        fn from_row(_: &Row) -> Result<Self, BoxError> {
+            Err("unused".into())
+        }
+
+        fn columns() -> &'static [&'static str] {
+            &["id", "name", "email"]
+        }
+
+        fn accepts_column(column: &str, ty: &Type) -> Option<bool> {
+            match column {
+                "id" => Some(*ty == Type::INT4),
+                "email" => Some(*ty == Type::TEXT),
+                _ => None,
+            }
+        }
+    }
+
+    #[test]
+    fn matches() {
+        let columns = [("email", Type::TEXT.oid()), ("id", Type::INT4.oid()), ("name", Type::BYTEA.oid()), ("extra", 0)];
+        let report = compare::<User>("users", &columns);
+        assert!(report.is_ok());
+        assert_eq!(report.to_string(), "table `users` matches");
+    }
+
+    #[test]
+    fn lists_mismatches_in_column_order() {
+        let columns = [("name", Type::TEXT.oid()), ("id", Type::INT8.oid())];
+        let report = compare::<User>("users", &columns);
+        assert_eq!(
+            report.issues(),
+            [
+                SchemaIssue::TypeMismatch { column: "id", found: Type::INT8 },
+                SchemaIssue::MissingColumn { column: "email" },
+            ]
+        );
+        assert_eq!(report.to_string(), "table `users` doesn't match: column `id` has type int8; column `email` is missing");
+    }
+
+    #[test]
+    fn skips_the_type_check_of_unknown_types() {
+        // A user defined type, e.g. an enum, has an oid `tokio_postgres` doesn't know.
+        let columns = [("id", 1_000_000), ("name", Type::TEXT.oid()), ("email", Type::TEXT.oid())];
+        assert!(compare::<User>("users", &columns).is_ok());
+    }
+}
@@ -0,0 +1,92 @@
+//! `sqlx` style call sites on top of `tokio-postgres`, enabled by the `sqlx-compat` feature.
+//!
+//! Structs deriving [`FromRow`] or [`TryFromRow`](crate::TryFromRow) can be fetched with the same
+//! `query_as(..).bind(..).fetch_*(..)` chain `sqlx` uses, which eases moving code between the two crates.
+//! The executor is any [`GenericClient`] (`Client` or `Transaction`) instead of a pool.
+//!
+//! `sqlx` field attributes map to `#[column(..)]` as follows:
+//!
+//! | `sqlx`                         | `tokio-postgres-utils`          |
+//! |--------------------------------|---------------------------------|
+//! | `#[sqlx(rename = "..")]`       | `#[column(rename = "..")]`      |
+//! | `#[sqlx(rename_all = "..")]`   | `#[column(rename_all = "..")]`  |
+//! | `#[sqlx(flatten)]`             | `#[column(flatten)]`            |
+//! | `#[sqlx(skip)]`                | `#[column(skip)]`               |
+//! | `#[sqlx(default)]`             | `Option<T>` field or `#[column(skip)]` |
+//! | `#[sqlx(try_from = "..")]`     | `TryFromRow` with a `FromSql` field type |
+//!
+//! ## Example
+//!
+//! ```rust, no_run
+//! use tokio_postgres_utils::{sqlx_compat, FromRow};
+//!
+//! #[derive(FromRow)]
+//! struct User {
+//!     id: i32,
+//!     name: String,
+//! }
+//!
+//! # async fn example(client: &tokio_postgres::Client) -> Result<(), tokio_postgres_utils::BoxError> {
+//! let users: Vec<User> = sqlx_compat::query_as("SELECT id, name FROM users WHERE age > $1")
+//!     .bind(18)
+//!     .fetch_all(client)
+//!     .await?;
+//!
+//! let user: Option<User> = sqlx_compat::query_as("SELECT id, name FROM users WHERE id = $1")
+//!     .bind(1)
+//!     .fetch_optional(client)
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{BoxError, FromRow};
+use std::marker::PhantomData;
+use tokio_postgres::{types::ToSql, GenericClient};
+
+/// Starts a query whose rows are mapped into `T`, like `sqlx::query_as`.
+pub fn query_as<'q, T: FromRow>(sql: &'q str) -> QueryAs<'q, T> {
+    QueryAs {
+        sql,
+        params: Vec::new(),
+        _marker: PhantomData,
+    }
+}
+
+/// A query built by [`query_as`], with its bound parameters.
+#[must_use = "the query does nothing until it is fetched"]
+pub struct QueryAs<'q, T> {
+    sql: &'q str,
+    params: Vec<Box<dyn ToSql + Sync + Send + 'q>>,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<'q, T: FromRow> QueryAs<'q, T> {
+    /// Binds the next parameter, `$1` for the first call, `$2` for the second and so on.
+    pub fn bind(mut self, value: impl ToSql + Sync + Send + 'q) -> Self {
+        self.params.push(Box::new(value));
+        self
+    }
+
+    /// Executes the query, mapping every row.
+    pub async fn fetch_all(self, client: &impl GenericClient) -> Result<Vec<T>, BoxError> {
+        let rows = client.query(self.sql, &self.to_params()).await?;
+        rows.iter().map(T::from_row).collect()
+    }
+
+    /// Executes the query, mapping the single row it must return.
+    pub async fn fetch_one(self, client: &impl GenericClient) -> Result<T, BoxError> {
+        let row = client.query_one(self.sql, &self.to_params()).await?;
+        T::from_row(&row)
+    }
+
+    /// Executes the query, mapping the row it returns, if any.
+    pub async fn fetch_optional(self, client: &impl GenericClient) -> Result<Option<T>, BoxError> {
+        let row = client.query_opt(self.sql, &self.to_params()).await?;
+        row.as_ref().map(T::from_row).transpose()
+    }
+
+    fn to_params(&self) -> Vec<&(dyn ToSql + Sync)> {
+        self.params.iter().map(|p| &**p as &(dyn ToSql + Sync)).collect()
+    }
+}
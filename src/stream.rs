@@ -0,0 +1,42 @@
+use crate::{BoxError, FromRow};
+use futures_util::{Stream, StreamExt, TryStream, TryStreamExt};
+use tokio_postgres::Row;
+
+/// Maps the rows of a stream, such as the `RowStream` of `query_raw`, into [`FromRow`] types.
+///
+/// ## Example
+///
+/// ```rust, no_run
+/// use futures_util::TryStreamExt;
+/// use tokio_postgres_utils::{FromRow, RowStreamExt};
+///
+/// #[derive(FromRow)]
+/// struct User {
+///     id: i32,
+///     name: String,
+/// }
+///
+/// # async fn example(client: &tokio_postgres::Client) -> Result<(), tokio_postgres_utils::BoxError> {
+/// let rows = client.query_raw("SELECT id, name FROM users", [] as [i32; 0]).await?;
+/// let admins: Vec<User> = rows
+///     .and_then_as::<User>()
+///     .try_filter(|user| std::future::ready(user.name.starts_with("admin")))
+///     .try_collect()
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+pub trait RowStreamExt: TryStream<Ok = Row> + Sized {
+    /// Maps every row into `T`, yielding both the errors of the stream and those of the mapping as [`BoxError`]s.
+    fn and_then_as<T: FromRow>(self) -> impl Stream<Item = Result<T, BoxError>>;
+}
+
+impl<S> RowStreamExt for S
+where
+    S: TryStream<Ok = Row>,
+    S::Error: Into<BoxError>,
+{
+    fn and_then_as<T: FromRow>(self) -> impl Stream<Item = Result<T, BoxError>> {
+        self.into_stream().map(|row| T::from_row(&row.map_err(Into::into)?))
+    }
+}
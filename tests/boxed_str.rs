@@ -0,0 +1,29 @@
+mod common;
+
+use tokio_postgres::types::Type;
+use tokio_postgres_utils::{BoxError, FromRow, Insert, TryFromRow};
+
+// Decoded as is, like `String` but without spare capacity.
+#[derive(TryFromRow, Insert)]
+struct Tag {
+    name: Box<str>,
+    note: Option<Box<str>>,
+}
+
+#[test]
+fn boxed_str_is_text() {
+    assert_eq!(<Tag as FromRow>::accepts_column("name", &Type::TEXT), Some(true));
+    assert_eq!(<Tag as FromRow>::accepts_column("name", &Type::INT4), Some(false));
+    assert_eq!(<Tag as Insert>::PARAM_TYPES, [Some(Type::TEXT), Some(Type::TEXT)]);
+}
+
+#[tokio::test]
+async fn boxed_str_reads_text() -> Result<(), BoxError> {
+    let Some(client) = common::connect().await else {
+        return Ok(());
+    };
+    let row = client.query_one("SELECT 'rust'::text AS name, NULL::text AS note", &[]).await?;
+    let tag = Tag::try_from(&row)?;
+    assert_eq!((&*tag.name, tag.note), ("rust", None));
+    Ok(())
+}
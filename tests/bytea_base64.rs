@@ -0,0 +1,50 @@
+#![cfg(feature = "base64")]
+
+mod common;
+
+use common::FakeRow;
+use tokio_postgres::types::Type;
+use tokio_postgres_utils::{BoxError, FromRow, FromRowAccess, Insert, TryFromRow};
+
+#[derive(Debug, PartialEq, TryFromRow, Insert)]
+#[column(row_access)]
+struct Avatar {
+    #[column(bytea_base64)]
+    image: String,
+    #[column(bytea_base64)]
+    thumbnail: Option<String>,
+}
+
+/// A row of the `bytea` values `Insert` binds.
+fn written(avatar: &Avatar) -> Result<FakeRow, BoxError> {
+    Avatar::COLUMNS
+        .iter()
+        .zip(avatar.as_params())
+        .try_fold(FakeRow::default(), |row, (column, param)| row.try_with(column, Type::BYTEA, param))
+}
+
+#[test]
+fn round_trip() -> Result<(), BoxError> {
+    let avatar = Avatar { image: "AAEC/w==".into(), thumbnail: None };
+    let row = written(&avatar)?;
+    assert_eq!(row.raw("image"), Some(&[0, 1, 2, 255][..]));
+    assert_eq!(row.raw("thumbnail"), None);
+    assert_eq!(Avatar::from_row_access(&row)?, avatar);
+
+    let avatar = Avatar { image: String::new(), thumbnail: Some("aGk=".into()) };
+    assert_eq!(Avatar::from_row_access(&written(&avatar)?)?, avatar);
+    Ok(())
+}
+
+#[test]
+fn binds_bytea() {
+    assert_eq!(<Avatar as Insert>::PARAM_TYPES, [Some(Type::BYTEA), Some(Type::BYTEA)]);
+    assert_eq!(<Avatar as FromRow>::accepts_column("image", &Type::BYTEA), Some(true));
+    assert_eq!(<Avatar as FromRow>::accepts_column("image", &Type::TEXT), Some(false));
+}
+
+#[test]
+fn rejects_invalid_base64() {
+    let avatar = Avatar { image: "not base64".into(), thumbnail: None };
+    assert!(written(&avatar).is_err());
+}
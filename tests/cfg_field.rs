@@ -0,0 +1,29 @@
+// Only the columns are checked, so the fields are never read.
+#![allow(dead_code)]
+
+use tokio_postgres_utils::{FromRow, Insert};
+
+#[derive(FromRow, Insert)]
+#[column(table = "users")]
+struct User {
+    id: i32,
+    #[cfg(feature = "decimal")]
+    balance: String,
+    #[cfg_attr(not(feature = "decimal"), column(skip))]
+    note: Option<String>,
+}
+
+// A field behind a feature is only read when the feature is enabled,
+// run with and without e.g. `--features decimal` to check both builds.
+#[test]
+fn cfg_fields_follow_the_features() {
+    if cfg!(feature = "decimal") {
+        assert_eq!(User::select_sql(), "SELECT id, balance, note FROM users");
+        assert_eq!(<User as Insert>::COLUMNS, ["id", "balance", "note"]);
+        assert!(User::skipped_fields().is_empty());
+    } else {
+        assert_eq!(User::select_sql(), "SELECT id FROM users");
+        assert_eq!(<User as Insert>::COLUMNS, ["id"]);
+        assert_eq!(User::skipped_fields(), ["note"]);
+    }
+}
@@ -0,0 +1,53 @@
+mod common;
+
+use common::FakeRow;
+use tokio_postgres::types::Type;
+use tokio_postgres_utils::{BoxError, FromRowAccess, MultiDecodeError, TryFromRow};
+
+#[derive(Debug, PartialEq, TryFromRow)]
+#[column(row_access, collect_errors)]
+struct Signup {
+    age: i32,
+    #[column(rename = "mail")]
+    email: String,
+    nickname: Option<String>,
+}
+
+#[test]
+fn decodes_every_field() -> Result<(), BoxError> {
+    let row = FakeRow::default()
+        .with("age", Type::INT4, &30)
+        .with("mail", Type::TEXT, &"ada@example.com")
+        .with("nickname", Type::TEXT, &None::<String>);
+    let signup = Signup::from_row_access(&row)?;
+    assert_eq!(signup, Signup { age: 30, email: "ada@example.com".into(), nickname: None });
+    Ok(())
+}
+
+#[test]
+fn lists_every_failed_column() {
+    let row = FakeRow::default()
+        .with("age", Type::TEXT, &"x")
+        .with("mail", Type::TEXT, &None::<String>)
+        .with("nickname", Type::TEXT, &"ada");
+    let err = Signup::from_row_access(&row).unwrap_err();
+    let err = err.downcast_ref::<MultiDecodeError>().unwrap();
+    let columns: Vec<_> = err.errors().iter().map(|(column, _)| *column).collect();
+    assert_eq!(columns, ["age", "mail"]);
+    assert_eq!(
+        err.to_string(),
+        format!("failed to decode 2 column(s): `age`: {}; `mail`: {}", err.errors()[0].1, err.errors()[1].1)
+    );
+}
+
+#[tokio::test]
+async fn try_from_returns_multi_decode_error() -> Result<(), BoxError> {
+    let Some(client) = common::connect().await else {
+        return Ok(());
+    };
+    let row = client.query_one("SELECT 'x' AS age, NULL::text AS mail, 'ada' AS nickname", &[]).await?;
+    let err: MultiDecodeError = Signup::try_from(&row).unwrap_err();
+    let columns: Vec<_> = err.errors().iter().map(|(column, _)| *column).collect();
+    assert_eq!(columns, ["age", "mail"]);
+    Ok(())
+}
@@ -0,0 +1,36 @@
+// Only the mapped columns are checked, so the fields are never read.
+#![allow(dead_code)]
+
+use tokio_postgres_utils::FromRow;
+
+#[derive(FromRow)]
+#[column(column_prefix = "usr_", rename_all = "camelCase")]
+struct User {
+    id: i32,
+    display_name: String,
+    #[column(rename = "created_at")]
+    created: String,
+}
+
+#[derive(FromRow)]
+struct Token {
+    r#type: String,
+    r#ref: i32,
+}
+
+#[derive(FromRow)]
+#[column(column_prefix = "tok_")]
+struct Prefixed {
+    r#type: String,
+}
+
+#[test]
+fn prefixes_default_column_names() {
+    assert_eq!(User::mapped_columns(), ["usr_id", "usr_displayName", "created_at"]);
+}
+
+#[test]
+fn raw_field_names_are_unquoted() {
+    assert_eq!(Token::mapped_columns(), ["type", "ref"]);
+    assert_eq!(Prefixed::mapped_columns(), ["tok_type"]);
+}
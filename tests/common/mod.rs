@@ -0,0 +1,56 @@
+// Each test crate uses only some of these helpers.
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+use tokio_postgres::{
+    types::{private::BytesMut, FromSql, IsNull, ToSql, Type},
+    Client, NoTls,
+};
+use tokio_postgres_utils::{BoxError, RowAccess};
+
+/// Connects to the database named by `DATABASE_URL`, e.g. `host=localhost user=postgres`.
+///
+/// Returns `None` when it isn't set, so tests needing a database pass without one.
+pub async fn connect() -> Option<Client> {
+    let config = std::env::var("DATABASE_URL").ok()?;
+    let (client, connection) = tokio_postgres::connect(&config, NoTls).await.expect("failed to connect to `DATABASE_URL`");
+    tokio::spawn(connection);
+    Some(client)
+}
+
+/// A row of binary encoded values by column name, read through `RowAccess` without a database.
+#[derive(Default)]
+pub struct FakeRow(HashMap<&'static str, (Type, Option<Vec<u8>>)>);
+
+impl FakeRow {
+    /// Adds `column` of type `ty`, holding `value` as encoded by its `ToSql` impl.
+    pub fn with(self, column: &'static str, ty: Type, value: &(dyn ToSql + Sync)) -> Self {
+        self.try_with(column, ty, value).unwrap()
+    }
+
+    /// Like [`with`](Self::with), failing instead of panicking when `value` can't be encoded.
+    pub fn try_with(mut self, column: &'static str, ty: Type, value: &(dyn ToSql + Sync)) -> Result<Self, BoxError> {
+        let mut out = BytesMut::new();
+        let raw = match value.to_sql_checked(&ty, &mut out)? {
+            IsNull::Yes => None,
+            IsNull::No => Some(out.to_vec()),
+        };
+        self.0.insert(column, (ty, raw));
+        Ok(self)
+    }
+
+    /// The encoded value of `column`, `None` when it is `NULL` or missing.
+    pub fn raw(&self, column: &str) -> Option<&[u8]> {
+        self.0.get(column)?.1.as_deref()
+    }
+}
+
+impl RowAccess for FakeRow {
+    fn try_get<'a, T: FromSql<'a>>(&'a self, column: &str) -> Result<T, BoxError> {
+        let (ty, raw) = self.0.get(column).ok_or_else(|| format!("no column `{column}`"))?;
+        if !T::accepts(ty) {
+            return Err(format!("column `{column}` is not a {ty}").into());
+        }
+        T::from_sql_nullable(ty, raw.as_deref())
+    }
+}
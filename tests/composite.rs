@@ -0,0 +1,72 @@
+mod common;
+
+use tokio_postgres_utils::{BoxError, ClientExt, TryFromRow};
+
+#[derive(Debug, PartialEq, TryFromRow)]
+#[column(composite)]
+struct Child {
+    id: i32,
+    name: String,
+}
+
+#[derive(Debug, PartialEq, TryFromRow)]
+struct Parent {
+    id: i32,
+    #[column(array_of_composite)]
+    children: Vec<Child>,
+}
+
+#[derive(Debug, PartialEq, TryFromRow)]
+#[column(from_column = "payload")]
+struct Payload {
+    kind: String,
+    amount: i32,
+}
+
+#[tokio::test]
+async fn array_agg_reads_into_structs() -> Result<(), BoxError> {
+    let Some(client) = common::connect().await else {
+        return Ok(());
+    };
+    client
+        .batch_execute(
+            "CREATE TEMP TABLE parents (id int4 PRIMARY KEY);
+             CREATE TEMP TABLE child (id int4 PRIMARY KEY, parent_id int4 NOT NULL, name text NOT NULL);
+             INSERT INTO parents VALUES (1), (2);
+             INSERT INTO child VALUES (10, 1, 'a'), (11, 1, 'b'), (12, 2, 'c');",
+        )
+        .await?;
+    let rows = client
+        .query(
+            "SELECT p.id, array_agg(c ORDER BY c.id) AS children FROM parents p JOIN child c ON c.parent_id = p.id GROUP BY p.id ORDER BY p.id",
+            &[],
+        )
+        .await?;
+    let parents = rows.iter().map(Parent::try_from).collect::<Result<Vec<_>, _>>()?;
+    let child = |id, name: &str| Child { id, name: name.to_owned() };
+    assert_eq!(
+        parents,
+        [
+            Parent { id: 1, children: vec![child(10, "a"), child(11, "b")] },
+            Parent { id: 2, children: vec![child(12, "c")] },
+        ]
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn from_column_reads_one_composite_column() -> Result<(), BoxError> {
+    let Some(client) = common::connect().await else {
+        return Ok(());
+    };
+    // The composite fields are in another order than the struct fields, as they are read by name.
+    client.batch_execute("CREATE TYPE pg_temp.event_payload AS (amount int4, kind text)").await?;
+    let payloads: Vec<Payload> = client
+        .query_as("SELECT 1 AS id, ROW(5, 'refund')::pg_temp.event_payload AS payload", ())
+        .await?;
+    assert_eq!(payloads, [Payload { kind: "refund".to_owned(), amount: 5 }]);
+
+    let row = client.query_one("SELECT 1 AS id, NULL::pg_temp.event_payload AS payload", &[]).await?;
+    assert!(Payload::try_from(&row).is_err());
+    Ok(())
+}
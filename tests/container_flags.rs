@@ -0,0 +1,40 @@
+// Only the generated SQL and columns are checked, so the fields are never read.
+#![allow(dead_code)]
+
+use std::{collections::HashMap, sync::LazyLock};
+use tokio_postgres_utils::FromRow;
+
+#[derive(FromRow)]
+#[column(table = "users", quote_identifiers = false)]
+struct Plain {
+    id: i32,
+}
+
+#[derive(FromRow)]
+#[column(table = "users", quote_identifiers = true)]
+struct Quoted {
+    id: i32,
+}
+
+#[derive(FromRow)]
+#[column(strip_trailing_underscore = false)]
+struct Kept {
+    type_: String,
+}
+
+static NAMES: LazyLock<HashMap<&str, &str>> = LazyLock::new(HashMap::new);
+
+// `lenient` can't be combined with `name_map`, so this only compiles while it's off.
+#[derive(FromRow)]
+#[column(lenient = false, case_insensitive = false, name_map = NAMES)]
+struct Mapped {
+    total: i32,
+}
+
+#[test]
+fn flags_take_an_optional_bool() {
+    assert_eq!(Plain::select_sql(), "SELECT id FROM users");
+    assert_eq!(Quoted::select_sql(), r#"SELECT "id" FROM users"#);
+    assert_eq!(Kept::mapped_columns(), ["type_"]);
+    assert_eq!(Mapped::mapped_columns(), ["total"]);
+}
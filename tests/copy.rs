@@ -0,0 +1,77 @@
+mod common;
+
+use futures_util::TryStreamExt;
+use tokio_postgres::types::Type;
+use tokio_postgres_utils::{copy_in_as, copy_out_as, BoxError, ClientExt, FromRow, Insert, TryFromRow};
+
+#[derive(Debug, PartialEq, TryFromRow)]
+#[column(binary_copy)]
+struct User {
+    id: i32,
+    name: Option<String>,
+}
+
+#[derive(Debug, PartialEq, FromRow, Insert)]
+struct NewUser {
+    id: i32,
+    name: Option<String>,
+}
+
+#[tokio::test]
+async fn copy_out_as_decodes_rows() -> Result<(), BoxError> {
+    let Some(client) = common::connect().await else {
+        return Ok(());
+    };
+    let users: Vec<User> = copy_out_as(
+        &client,
+        "COPY (SELECT g, NULLIF('user ' || g, 'user 2') FROM generate_series(1, 3) g) TO STDOUT (FORMAT binary)",
+        &[Type::INT4, Type::TEXT],
+    )
+    .await?
+    .try_collect()
+    .await?;
+    assert_eq!(
+        users,
+        [
+            User { id: 1, name: Some("user 1".into()) },
+            User { id: 2, name: None },
+            User { id: 3, name: Some("user 3".into()) },
+        ]
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn copy_out_as_fails_on_mismatched_types() -> Result<(), BoxError> {
+    let Some(client) = common::connect().await else {
+        return Ok(());
+    };
+    let users = copy_out_as::<User>(&client, "COPY (SELECT 1::int8, 'a') TO STDOUT (FORMAT binary)", &[Type::INT4, Type::TEXT])
+        .await?
+        .try_collect::<Vec<_>>()
+        .await;
+    assert!(users.is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn copy_in_as_writes_rows() -> Result<(), BoxError> {
+    let Some(client) = common::connect().await else {
+        return Ok(());
+    };
+    // The columns are declared out of `Insert::COLUMNS` order, with an extra one left to its default.
+    client.batch_execute("CREATE TEMP TABLE copy_in_users (name text, created bool DEFAULT true, id int4)").await?;
+    let users = (1..=3).map(|id| NewUser { id, name: (id != 2).then(|| format!("user {id}")) });
+    assert_eq!(copy_in_as(&client, "copy_in_users", users).await?, 3);
+
+    let written: Vec<NewUser> = client.query_as("SELECT id, name FROM copy_in_users WHERE created ORDER BY id", ()).await?;
+    assert_eq!(
+        written,
+        [
+            NewUser { id: 1, name: Some("user 1".into()) },
+            NewUser { id: 2, name: None },
+            NewUser { id: 3, name: Some("user 3".into()) },
+        ]
+    );
+    Ok(())
+}
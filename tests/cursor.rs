@@ -0,0 +1,43 @@
+mod common;
+
+use tokio_postgres_utils::{BoxError, Cursor, TryFromRow};
+
+#[derive(TryFromRow)]
+struct Item {
+    id: i32,
+}
+
+#[tokio::test]
+async fn pages_through_every_row() -> Result<(), BoxError> {
+    let Some(client) = common::connect().await else {
+        return Ok(());
+    };
+    let mut cursor = Cursor::<_, Item, i32>::new(&client, "SELECT n AS id FROM generate_series(25, 1, -1) AS n", "id", 10);
+    let mut pages = Vec::new();
+    while let Some(page) = cursor.next_page().await? {
+        pages.push(page.iter().map(|item| item.id).collect::<Vec<_>>());
+    }
+    assert_eq!(pages.iter().map(Vec::len).collect::<Vec<_>>(), [10, 10, 5]);
+    assert_eq!(pages[0][0], 1);
+    assert_eq!(pages[2], [21, 22, 23, 24, 25]);
+    assert!(cursor.next_page().await?.is_none());
+    Ok(())
+}
+
+#[tokio::test]
+async fn resumes_after_a_key() -> Result<(), BoxError> {
+    let Some(client) = common::connect().await else {
+        return Ok(());
+    };
+    let query = "SELECT n AS id FROM generate_series(1, 20) AS n";
+    let mut cursor = Cursor::<_, Item, i32>::new(&client, query, "id", 10).after(15);
+    let page = cursor.next_page().await?.unwrap();
+    assert_eq!(page.iter().map(|item| item.id).collect::<Vec<_>>(), [16, 17, 18, 19, 20]);
+    assert!(cursor.next_page().await?.is_none());
+
+    // A last page as long as the page size is followed by an empty query, then `None`.
+    let mut cursor = Cursor::<_, Item, i32>::new(&client, query, "id", 10).after(10);
+    assert_eq!(cursor.next_page().await?.unwrap().len(), 10);
+    assert!(cursor.next_page().await?.is_none());
+    Ok(())
+}
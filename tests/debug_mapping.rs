@@ -0,0 +1,42 @@
+#![cfg(feature = "debug-mapping")]
+// The fields are only described by `DEBUG_MAPPING`, never read.
+#![allow(dead_code)]
+
+use tokio_postgres_utils::{FromRow, TryFromRow};
+
+#[derive(FromRow)]
+struct Address {
+    city: String,
+}
+
+#[derive(FromRow)]
+#[column(rename_all = "camelCase")]
+struct User {
+    user_id: i32,
+    #[column(rename = "nm")]
+    name: String,
+    #[column(skip)]
+    cache: Option<String>,
+    #[column(flatten)]
+    address: Address,
+}
+
+#[derive(TryFromRow)]
+#[column(case_insensitive)]
+struct Admin {
+    id: i32,
+    #[column(ordinal = 2)]
+    level: i32,
+}
+
+#[test]
+fn debug_mapping() {
+    assert_eq!(
+        User::DEBUG_MAPPING,
+        "column mapping of `User`:\n    user_id <- \"userId\"\n    name <- \"nm\"\n    cache <- skipped\n    address <- flattened"
+    );
+    assert_eq!(
+        Admin::DEBUG_MAPPING,
+        "column mapping of `Admin`:\n    id <- \"id\" (case-insensitive)\n    level <- column #2"
+    );
+}
@@ -0,0 +1,22 @@
+mod common;
+
+use common::FakeRow;
+use tokio_postgres::types::Type;
+use tokio_postgres_utils::{BoxError, FromRowAccess, TryFromRow};
+
+// Each field decoded as is gets a `FromSqlOwned` bound, so `T` needs no bound of its own.
+#[derive(Debug, PartialEq, TryFromRow)]
+#[column(row_access)]
+struct Wrapper<T> {
+    id: i32,
+    value: T,
+}
+
+#[test]
+fn generic_fields_are_bound_at_the_field() -> Result<(), BoxError> {
+    let row = FakeRow::default().with("id", Type::INT4, &1).with("value", Type::TEXT, &"a");
+    assert_eq!(Wrapper::<String>::from_row_access(&row)?, Wrapper { id: 1, value: "a".to_owned() });
+    let row = FakeRow::default().with("id", Type::INT4, &1).with("value", Type::INT8, &2i64);
+    assert_eq!(Wrapper::<i64>::from_row_access(&row)?, Wrapper { id: 1, value: 2 });
+    Ok(())
+}
@@ -0,0 +1,65 @@
+#![cfg(feature = "error-context")]
+// Only the errors are checked, so the fields are never read.
+#![allow(dead_code)]
+
+mod common;
+
+use common::FakeRow;
+use tokio_postgres::types::Type;
+use tokio_postgres_utils::{BoxError, ColumnError, FromRow, FromRowAccess, TryFromRow};
+
+#[derive(TryFromRow)]
+#[column(rename_all = "camelCase")]
+struct User {
+    user_id: i32,
+}
+
+#[derive(TryFromRow)]
+struct Event {
+    #[column(from_str)]
+    port: u16,
+}
+
+#[tokio::test]
+async fn context_keeps_the_error_type() -> Result<(), BoxError> {
+    let Some(client) = common::connect().await else {
+        return Ok(());
+    };
+    let row = client.query_one(r#"SELECT 'x' AS "userId""#, &[]).await?;
+    let _: tokio_postgres::Error = User::try_from(&row).err().unwrap();
+    let err = User::from_row(&row).err().unwrap();
+    assert_eq!(err.downcast_ref::<ColumnError>().unwrap().column(), "userId");
+    Ok(())
+}
+
+#[tokio::test]
+async fn context_wraps_boxed_errors() -> Result<(), BoxError> {
+    let Some(client) = common::connect().await else {
+        return Ok(());
+    };
+    let row = client.query_one(r#"SELECT 'x' AS port"#, &[]).await?;
+    let err: BoxError = Event::try_from(&row).err().unwrap();
+    assert_eq!(err.downcast_ref::<ColumnError>().unwrap().column(), "port");
+    Ok(())
+}
+
+#[derive(TryFromRow)]
+#[column(row_access, rename_all = "camelCase")]
+struct Profile {
+        user_id: i32,
+        #[column(rename = "display_name")]
+    name: String,
+}
+
+// Decode errors name the column as read from the row, after `rename` and `rename_all`.
+#[test]
+fn context_names_the_renamed_column() {
+    let row = FakeRow::default().with("userId", Type::INT4, &7).with("display_name", Type::TEXT, &None::<String>);
+    let err = Profile::from_row_access(&row).err().unwrap();
+    let err = err.downcast_ref::<ColumnError>().unwrap();
+    assert_eq!(err.column(), "display_name");
+    assert!(err.to_string().starts_with("error decoding column `display_name`: "));
+
+    let err = Profile::from_row_access(&FakeRow::default()).err().unwrap();
+    assert_eq!(err.to_string(), "error decoding column `userId`: no column `userId`");
+}
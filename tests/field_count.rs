@@ -0,0 +1,35 @@
+// Only the counts are checked, so the fields are never read.
+#![allow(dead_code)]
+
+use tokio_postgres_utils::{FromRow, TryFromRow};
+
+#[derive(TryFromRow)]
+struct Address {
+    city: String,
+    zip: String,
+}
+
+#[derive(TryFromRow)]
+struct User {
+    id: i32,
+    #[column(flatten)]
+    address: Address,
+    #[column(skip)]
+    cache: Vec<u8>,
+}
+
+#[derive(FromRow)]
+struct Pair(i32, String);
+
+#[derive(FromRow)]
+struct Unit;
+
+// `FIELD_COUNT` counts every field that isn't skipped, flattened ones as one field each.
+const _: () = assert!(User::FIELD_COUNT == 2);
+
+#[test]
+fn field_count_counts_unskipped_fields() {
+    assert_eq!(Address::FIELD_COUNT, <Address as FromRow>::columns().len());
+    assert_eq!(Pair::FIELD_COUNT, 2);
+    assert_eq!(Unit::FIELD_COUNT, 0);
+}
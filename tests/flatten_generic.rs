@@ -0,0 +1,34 @@
+mod common;
+
+use tokio_postgres_utils::{BoxError, TryFromRow};
+
+#[derive(TryFromRow)]
+struct Repo<Id> {
+    id: Id,
+    stars: i64,
+}
+
+#[derive(TryFromRow)]
+#[column(table = "listings")]
+struct Listing {
+    #[column(flatten)]
+    repo: Repo<i32>,
+    title: String,
+}
+
+#[test]
+fn flattened_generic_columns_are_selected() {
+    assert_eq!(Listing::select_sql(), "SELECT id, stars, title FROM listings");
+}
+
+// A flattened generic type can be instantiated with a concrete argument.
+#[tokio::test]
+async fn flattened_generic_reads_its_columns() -> Result<(), BoxError> {
+    let Some(client) = common::connect().await else {
+        return Ok(());
+    };
+    let row = client.query_one("SELECT 1 AS id, 5::int8 AS stars, 'utils' AS title", &[]).await?;
+    let listing = Listing::try_from(&row)?;
+    assert_eq!((listing.repo.id, listing.repo.stars, listing.title.as_str()), (1, 5, "utils"));
+    Ok(())
+}
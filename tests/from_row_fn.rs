@@ -0,0 +1,48 @@
+mod common;
+
+use tokio_postgres::Row;
+use tokio_postgres_utils::{BoxError, FromRow, RowBatch, TryFromRow};
+
+fn full_name(r: &Row) -> String {
+    format!("{} {}", r.get::<_, &str>("first_name"), r.get::<_, &str>("last_name"))
+}
+
+fn try_full_name(r: &Row) -> Result<String, tokio_postgres::Error> {
+    Ok(format!("{} {}", r.try_get::<_, &str>("first_name")?, r.try_get::<_, &str>("last_name")?))
+}
+
+#[derive(FromRow)]
+struct User {
+    id: i32,
+    #[column(from_row = full_name)]
+    name: String,
+}
+
+#[derive(TryFromRow)]
+struct TryUser {
+    id: i32,
+    #[column(from_row = try_full_name)]
+    name: String,
+}
+
+#[test]
+fn from_row_fields_read_no_column_of_their_own() {
+    assert_eq!(User::mapped_columns(), ["id"]);
+}
+
+#[tokio::test]
+async fn from_row_fields_are_computed_from_the_row() -> Result<(), BoxError> {
+    let Some(client) = common::connect().await else {
+        return Ok(());
+    };
+    let rows = client.query("SELECT 1 AS id, 'Ada' AS first_name, 'Lovelace' AS last_name", &[]).await?;
+    let user = User::from(&rows[0]);
+    assert_eq!((user.id, user.name.as_str()), (1, "Ada Lovelace"));
+    let user = TryUser::try_from(&rows[0])?;
+    assert_eq!((user.id, user.name.as_str()), (1, "Ada Lovelace"));
+    assert_eq!(RowBatch::<TryUser>::new(&rows).next().unwrap()?.name, "Ada Lovelace");
+
+    let row = client.query_one("SELECT 1 AS id, 'Ada' AS first_name", &[]).await?;
+    assert_eq!(TryUser::try_from(&row).err().unwrap().to_string(), "invalid column `last_name`");
+    Ok(())
+}
@@ -0,0 +1,56 @@
+mod common;
+
+use std::collections::HashMap;
+use tokio_postgres_utils::{rows_to_map, try_from_rows_indexed, BoxError, TryFromRow, TryMapRows};
+
+#[derive(Debug, PartialEq, TryFromRow)]
+struct User {
+    id: i32,
+    name: String,
+}
+
+#[tokio::test]
+async fn try_map_collect_stops_at_the_first_failing_row() -> Result<(), BoxError> {
+    let Some(client) = common::connect().await else {
+        return Ok(());
+    };
+    let rows = client.query("SELECT * FROM (VALUES (1, 'nur'), (2, 'ada')) AS t(id, name)", &[]).await?;
+    let users = rows.try_map_collect::<User>()?;
+    assert_eq!(users, [User { id: 1, name: "nur".to_owned() }, User { id: 2, name: "ada".to_owned() }]);
+    assert!(rows[..0].try_map_collect::<User>()?.is_empty());
+
+    let rows = client.query("SELECT * FROM (VALUES (1, 'nur'), (NULL, 'ada')) AS t(id, name)", &[]).await?;
+    assert!(rows.try_map_collect::<User>().is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn try_from_rows_indexed_reports_the_failing_row() -> Result<(), BoxError> {
+    let Some(client) = common::connect().await else {
+        return Ok(());
+    };
+    let rows = client.query("SELECT * FROM (VALUES (1, 'a'), (2, 'b'), (NULL, 'c'), (4, NULL)) AS t(id, name)", &[]).await?;
+    let (index, err) = try_from_rows_indexed::<User>(&rows).err().unwrap();
+    assert_eq!(index, 2);
+    assert!(err.to_string().contains("error deserializing column 0"), "{err}");
+
+    let users = try_from_rows_indexed::<User>(&rows[..2]).map_err(|(_, err)| err)?;
+    assert_eq!(users.iter().map(|user| user.id).collect::<Vec<_>>(), [1, 2]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn rows_to_map_keeps_the_last_value_of_a_key() -> Result<(), BoxError> {
+    let Some(client) = common::connect().await else {
+        return Ok(());
+    };
+    let rows = client
+        .query("SELECT * FROM (VALUES ('theme', 1), ('lang', 2), ('theme', 3)) AS t(name, value)", &[])
+        .await?;
+    let settings: HashMap<String, i32> = rows_to_map(&rows, "name", "value")?;
+    assert_eq!(settings, HashMap::from([("theme".to_owned(), 3), ("lang".to_owned(), 2)]));
+
+    assert!(rows_to_map::<String, i32>(&rows, "name", "missing").is_err());
+    assert!(rows_to_map::<String, String>(&rows, "name", "value").is_err());
+    Ok(())
+}
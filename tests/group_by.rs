@@ -0,0 +1,53 @@
+mod common;
+
+use tokio_postgres_utils::{BoxError, ClientExt, FromRow};
+
+#[derive(FromRow)]
+#[column(quote_identifiers)]
+struct SalesByRegion {
+    #[column(group_key)]
+    region: String,
+    #[column(aggregate = "sum")]
+    total: i64,
+    #[column(skip)]
+    #[allow(dead_code)]
+    rank: usize,
+}
+
+#[derive(FromRow)]
+#[allow(dead_code)]
+struct Totals {
+    #[column(aggregate = "count")]
+    id: i64,
+    #[column(aggregate = "max")]
+    total: i32,
+}
+
+#[test]
+fn group_by_selects_keys_and_aggregates() {
+    assert_eq!(SalesByRegion::select_expr_sql(), r#""region", SUM("total") AS "total""#);
+    assert_eq!(SalesByRegion::group_by_sql(), r#"GROUP BY "region""#);
+}
+
+// Without keys, the whole result is one group.
+#[test]
+fn group_by_without_keys_is_empty() {
+    assert_eq!(Totals::select_expr_sql(), "COUNT(id) AS id, MAX(total) AS total");
+    assert_eq!(Totals::group_by_sql(), "");
+}
+
+#[tokio::test]
+async fn grouped_rows_read_back() -> Result<(), BoxError> {
+    let Some(client) = common::connect().await else {
+        return Ok(());
+    };
+    let sql = format!(
+        "SELECT {} FROM (VALUES ('eu', 2), ('eu', 3), ('us', 4)) AS sales(region, total) {} ORDER BY region",
+        SalesByRegion::select_expr_sql(),
+        SalesByRegion::group_by_sql(),
+    );
+    let sales: Vec<SalesByRegion> = client.query_as(&sql, ()).await?;
+    let sales: Vec<_> = sales.iter().map(|s| (s.region.as_str(), s.total)).collect();
+    assert_eq!(sales, [("eu", 5), ("us", 4)]);
+    Ok(())
+}
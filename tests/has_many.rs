@@ -0,0 +1,55 @@
+mod common;
+
+use tokio_postgres_utils::{BoxError, FromRow, TryFromRow};
+
+#[derive(Debug, PartialEq, FromRow)]
+struct Post {
+    post_id: i32,
+    title: String,
+}
+
+#[derive(Debug, PartialEq, FromRow)]
+struct Author {
+    id: i32,
+    name: String,
+    #[column(has_many(group_by = "id", child = Post))]
+    posts: Vec<Post>,
+}
+
+#[derive(Debug, PartialEq, TryFromRow)]
+struct TryAuthor {
+    id: i32,
+    #[column(has_many(group_by = "id", child = Post))]
+    posts: Vec<Post>,
+}
+
+const SQL: &str = "SELECT a.id, a.name, p.post_id, p.title FROM authors a LEFT JOIN posts p ON p.author_id = a.id ORDER BY a.id, p.post_id";
+
+#[tokio::test]
+async fn group_and_map_collects_the_children_of_each_parent() -> Result<(), BoxError> {
+    let Some(client) = common::connect().await else {
+        return Ok(());
+    };
+    client
+        .batch_execute(
+            "CREATE TEMP TABLE authors (id int4 PRIMARY KEY, name text NOT NULL);
+             CREATE TEMP TABLE posts (post_id int4 PRIMARY KEY, author_id int4 NOT NULL, title text NOT NULL);
+             INSERT INTO authors VALUES (1, 'ann'), (2, 'bob');
+             INSERT INTO posts VALUES (10, 1, 'x'), (11, 1, 'y'), (12, 1, 'z');",
+        )
+        .await?;
+    let rows = client.query(SQL, &[]).await?;
+    assert_eq!(rows.len(), 4);
+    let post = |post_id, title: &str| Post { post_id, title: title.to_owned() };
+    assert_eq!(
+        Author::group_and_map(&rows),
+        [
+            Author { id: 1, name: "ann".to_owned(), posts: vec![post(10, "x"), post(11, "y"), post(12, "z")] },
+            // The unmatched `LEFT JOIN` row has only `NULL` child columns, so it reads no child.
+            Author { id: 2, name: "bob".to_owned(), posts: vec![] },
+        ]
+    );
+    let authors = TryAuthor::group_and_map(&rows)?;
+    assert_eq!(authors.iter().map(|a| (a.id, a.posts.len())).collect::<Vec<_>>(), [(1, 3), (2, 0)]);
+    Ok(())
+}
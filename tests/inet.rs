@@ -0,0 +1,23 @@
+mod common;
+
+use std::net::IpAddr;
+use tokio_postgres_utils::{BoxError, ClientExt, TryFromRow};
+
+#[derive(TryFromRow)]
+struct Visit {
+    #[column(inet)]
+    client: IpAddr,
+    #[column(inet)]
+    proxy: Option<IpAddr>,
+}
+
+#[tokio::test]
+async fn inet_columns_read_into_ip_addr() -> Result<(), BoxError> {
+    let Some(client) = common::connect().await else {
+        return Ok(());
+    };
+    let visits: Vec<Visit> = client.query_as("SELECT '10.0.0.1'::inet AS client, NULL::inet AS proxy", ()).await?;
+    assert_eq!(visits[0].client, IpAddr::from([10, 0, 0, 1]));
+    assert_eq!(visits[0].proxy, None);
+    Ok(())
+}
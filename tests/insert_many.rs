@@ -0,0 +1,31 @@
+mod common;
+
+use tokio_postgres_utils::{insert_many, BoxError, Insert};
+
+#[derive(Insert)]
+struct NewUser {
+    id: i32,
+    name: String,
+    score: i64,
+}
+
+// 30,000 rows of 3 params each need 90,000, so they are split over three statements of at most 32767 params.
+#[tokio::test]
+async fn insert_many_crosses_the_param_limit() -> Result<(), BoxError> {
+    let Some(client) = common::connect().await else {
+        return Ok(());
+    };
+    client.batch_execute("CREATE TEMP TABLE users (id int4 PRIMARY KEY, name text NOT NULL, score int8 NOT NULL)").await?;
+    let users: Vec<NewUser> = (0..30_000).map(|id| NewUser { id, name: format!("user {id}"), score: id.into() }).collect();
+    assert_eq!(insert_many(&client, "users", &users).await?, 30_000);
+
+    let row = client.query_one("SELECT count(*), sum(score)::int8, min(id), max(id) FROM users", &[]).await?;
+    let summary: (i64, i64, i32, i32) = (row.get(0), row.get(1), row.get(2), row.get(3));
+    assert_eq!(summary, (30_000, (0..30_000).sum(), 0, 29_999));
+
+    // The last row of the first statement and the first of the second.
+    let rows = client.query("SELECT id, name FROM users WHERE id IN (10921, 10922) ORDER BY id", &[]).await?;
+    let rows: Vec<(i32, String)> = rows.iter().map(|row| (row.get(0), row.get(1))).collect();
+    assert_eq!(rows, [(10_921, "user 10921".to_owned()), (10_922, "user 10922".to_owned())]);
+    Ok(())
+}
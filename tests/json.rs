@@ -0,0 +1,130 @@
+#![cfg(feature = "json")]
+
+mod common;
+
+use serde_json::json;
+use tokio_postgres_utils::{row_to_json, BoxError, FromRow, Insert, TryFromRow};
+
+#[derive(TryFromRow)]
+struct Post {
+    id: i32,
+    #[column(json)]
+    comments: Vec<(i32, String)>,
+}
+
+// `skip_insert` only changes how the field is written, so it combines with `json`.
+#[derive(TryFromRow, Insert)]
+struct Draft {
+    title: String,
+    #[column(skip_insert, json)]
+    tags: Vec<String>,
+}
+
+#[derive(TryFromRow)]
+#[column(from_json_column = "data")]
+struct User {
+    id: i32,
+    name: String,
+}
+
+impl<'de> serde::Deserialize<'de> for User {
+    fn deserialize<D: serde::Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+        let mut map = serde_json::Map::<String, serde_json::Value>::deserialize(d)?;
+        let id = map.remove("id").and_then(|v| v.as_i64()).ok_or_else(|| D::Error::missing_field("id"))?;
+        let name = map.remove("name").and_then(|v| v.as_str().map(str::to_owned));
+        let name = name.ok_or_else(|| D::Error::missing_field("name"))?;
+        Ok(User { id: id as i32, name })
+    }
+}
+
+#[tokio::test]
+async fn json_fields_decode_jsonb_agg() -> Result<(), BoxError> {
+    let Some(client) = common::connect().await else {
+        return Ok(());
+    };
+    let row = client
+        .query_one(
+            "SELECT 1 AS id, jsonb_agg(jsonb_build_array(c.id, c.body) ORDER BY c.id) AS comments
+             FROM (VALUES (1, 'a'), (2, 'b')) AS c(id, body)",
+            &[],
+        )
+        .await?;
+    let post = Post::try_from(&row)?;
+    assert_eq!((post.id, post.comments), (1, vec![(1, "a".to_owned()), (2, "b".to_owned())]));
+
+    let row = client.query_one(r#"SELECT 1 AS id, '[{"id": 1}]'::jsonb AS comments"#, &[]).await?;
+    let err = Post::try_from(&row).err().unwrap();
+    assert!(err.to_string().contains("error decoding json field `comments`"), "{err}");
+    Ok(())
+}
+
+#[tokio::test]
+async fn skip_insert_json_fields_are_read_but_not_inserted() -> Result<(), BoxError> {
+    assert_eq!(Draft::COLUMNS, ["title"]);
+    let Some(client) = common::connect().await else {
+        return Ok(());
+    };
+    let row = client.query_one(r#"SELECT 'hello' AS title, '["a", "b"]'::jsonb AS tags"#, &[]).await?;
+    let draft = Draft::try_from(&row)?;
+    assert_eq!((draft.title.as_str(), draft.tags), ("hello", vec!["a".to_owned(), "b".to_owned()]));
+    Ok(())
+}
+
+// The whole struct is deserialized from one `to_jsonb` column, ignoring the others.
+#[tokio::test]
+async fn from_json_column_reads_one_column() -> Result<(), BoxError> {
+    assert_eq!(<User as FromRow>::columns(), ["data"]);
+    let Some(client) = common::connect().await else {
+        return Ok(());
+    };
+    let row = client
+        .query_one("SELECT 5 AS id, to_jsonb(u.*) AS data FROM (VALUES (1, 'nur')) AS u(id, name)", &[])
+        .await?;
+    let user = User::try_from(&row)?;
+    assert_eq!((user.id, user.name.as_str()), (1, "nur"));
+
+    let row = client.query_one("SELECT to_jsonb(u.*) AS data FROM (VALUES (1)) AS u(id)", &[]).await?;
+    let err = User::try_from(&row).err().unwrap();
+    assert!(err.to_string().ends_with("error decoding json field `data`: missing field `name`"), "{err}");
+    Ok(())
+}
+
+#[tokio::test]
+async fn row_to_json_converts_mixed_types() -> Result<(), BoxError> {
+    let Some(client) = common::connect().await else {
+        return Ok(());
+    };
+    let row = client
+        .query_one(
+            r#"SELECT 1 AS id, 'nur' AS name, NULL::text AS email, 1.5::float8 AS score, ARRAY[1, NULL] AS ids,
+                      '{"admin": true}'::jsonb AS flags, '\x0a0b'::bytea AS avatar, '2024-02-29'::date AS joined,
+                      '12:30:00.25'::time AS alarm, '2024-02-29 12:30:00'::timestamp AS local,
+                      '2024-02-29 12:30:00.5+02'::timestamptz AS created_at, 'infinity'::timestamptz AS expires_at,
+                      '-infinity'::date AS since"#,
+            &[],
+        )
+        .await?;
+    assert_eq!(
+        row_to_json(&row)?,
+        json!({
+            "id": 1,
+            "name": "nur",
+            "email": null,
+            "score": 1.5,
+            "ids": [1, null],
+            "flags": { "admin": true },
+            "avatar": "\\x0a0b",
+            "joined": "2024-02-29",
+            "alarm": "12:30:00.25",
+            "local": "2024-02-29T12:30:00",
+            "created_at": "2024-02-29T10:30:00.5Z",
+            "expires_at": "infinity",
+            "since": "-infinity",
+        })
+    );
+
+    let row = client.query_one("SELECT '1 day'::interval AS ttl", &[]).await?;
+    assert!(row_to_json(&row).is_err());
+    Ok(())
+}
@@ -0,0 +1,40 @@
+mod common;
+
+use tokio_postgres_utils::{BoxError, TryFromRow};
+
+struct Tenant {
+    prefix: &'static str,
+}
+
+fn name_column(tenant: &Tenant) -> String {
+    format!("{}_name", tenant.prefix)
+}
+
+#[derive(TryFromRow)]
+#[column(ctx = Tenant, rename_all = "UPPERCASE")]
+struct User {
+    id: i32,
+    #[column(name_fn = name_column)]
+    name: String,
+    #[column(name_fn = |tenant: &Tenant| format!("{}_email", tenant.prefix))]
+    email: Option<String>,
+}
+
+// A context changes the columns of the `name_fn` fields only, and only in `try_from_row_with_ctx`.
+#[tokio::test]
+async fn name_fn_reads_the_column_of_the_context() -> Result<(), BoxError> {
+    let Some(client) = common::connect().await else {
+        return Ok(());
+    };
+    let row = client
+        .query_one(
+            r#"SELECT 1 AS "ID", 'Ada' AS acme_name, NULL::text AS acme_email, 'Grace' AS "NAME", NULL::text AS "EMAIL""#,
+            &[],
+        )
+        .await?;
+    let user = User::try_from_row_with_ctx(&row, &Tenant { prefix: "acme" })?;
+    assert_eq!((user.id, user.name.as_str(), user.email), (1, "Ada", None));
+    assert_eq!(User::try_from(&row)?.name, "Grace");
+    assert!(User::try_from_row_with_ctx(&row, &Tenant { prefix: "other" }).is_err());
+    Ok(())
+}
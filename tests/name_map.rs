@@ -0,0 +1,28 @@
+mod common;
+
+use common::FakeRow;
+use std::{collections::HashMap, sync::LazyLock};
+use tokio_postgres::types::Type;
+use tokio_postgres_utils::{BoxError, FromRowAccess, TryFromRow};
+
+static NAMES: LazyLock<HashMap<&str, &str>> = LazyLock::new(|| HashMap::from([("total", "summe")]));
+
+#[derive(TryFromRow)]
+#[column(row_access, name_map = NAMES)]
+struct Sales {
+    total: i32,
+    #[column(rename = "jahr")]
+    year: i32,
+}
+
+// The map gives the columns of the fields it names, the other fields read their usual ones.
+#[test]
+fn name_map_renames_the_mapped_fields() -> Result<(), BoxError> {
+    let row = FakeRow::default().with("summe", Type::INT4, &5).with("jahr", Type::INT4, &2024);
+    let sales = Sales::from_row_access(&row)?;
+    assert_eq!((sales.total, sales.year), (5, 2024));
+
+    let row = FakeRow::default().with("total", Type::INT4, &5).with("jahr", Type::INT4, &2024);
+    assert!(Sales::from_row_access(&row).is_err());
+    Ok(())
+}
@@ -0,0 +1,46 @@
+mod common;
+
+use tokio_postgres_utils::{BoxError, FromRow, RowBatch, TryFromRow};
+
+#[derive(TryFromRow)]
+#[column(rename_all = "camelCase")]
+struct User {
+    user_id: i32,
+    #[column(ordinal = 2)]
+    display_name: String,
+    last_name: String,
+}
+
+// Only the columns are checked.
+#[allow(dead_code)]
+#[derive(FromRow)]
+struct Member {
+    id: i32,
+    #[column(ordinal = RowIndex(1))]
+    name: String,
+}
+
+#[test]
+fn ordinal_can_be_a_row_index() {
+    assert_eq!(<Member as FromRow>::columns(), ["id"]);
+}
+
+// An `ordinal` field reads its position, while the other fields of a `rename_all` struct are still renamed.
+#[tokio::test]
+async fn ordinal_reads_its_position() -> Result<(), BoxError> {
+    let Some(client) = common::connect().await else {
+        return Ok(());
+    };
+    let rows = client
+        .query(r#"SELECT 1 AS "userId", 'lovelace' AS "lastName", initcap('ada') AS display_name"#, &[])
+        .await?;
+    let user = User::try_from(&rows[0])?;
+    assert_eq!((user.user_id, user.display_name.as_str(), user.last_name.as_str()), (1, "Ada", "lovelace"));
+
+    let users = RowBatch::<User>::new(&rows).collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(users[0].display_name, "Ada");
+
+    let row = client.query_one(r#"SELECT 1 AS "userId", 'lovelace' AS "lastName""#, &[]).await?;
+    assert!(User::try_from(&row).is_err());
+    Ok(())
+}
@@ -0,0 +1,37 @@
+mod common;
+
+use tokio_postgres_utils::{row_to_pairs, BoxError};
+
+#[tokio::test]
+async fn row_to_pairs_writes_each_type_as_text() -> Result<(), BoxError> {
+    let Some(client) = common::connect().await else {
+        return Ok(());
+    };
+    let row = client
+        .query_one(
+            r#"SELECT 1 AS id, 'nur' AS name, NULL::text AS email, ARRAY[1, NULL] AS ids, '\x0a0b'::bytea AS avatar,
+                      '{"admin": true}'::jsonb AS flags, '10.0.0.1'::inet AS ip, '2000-01-02'::date AS joined,
+                      ARRAY['2000-01-02 03:04:05.5+00'::timestamptz] AS seen, '1 day'::interval AS ttl"#,
+            &[],
+        )
+        .await?;
+    let pairs = row_to_pairs(&row);
+    let pairs: Vec<(&str, &str)> = pairs.iter().map(|(column, value)| (column.as_str(), value.as_str())).collect();
+    assert_eq!(
+        pairs,
+        [
+            ("id", "1"),
+            ("name", "nur"),
+            ("email", "NULL"),
+            ("ids", "{1,NULL}"),
+            ("avatar", "\\x0a0b"),
+            ("flags", r#"{"admin": true}"#),
+            ("ip", "10.0.0.1"),
+            ("joined", "2000-01-02"),
+            ("seen", "{2000-01-02T03:04:05.5Z}"),
+            // `interval` has no text form here, so its raw bytes are written.
+            ("ttl", "interval(\\x00000000000000000000000100000000)"),
+        ]
+    );
+    Ok(())
+}
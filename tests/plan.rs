@@ -0,0 +1,71 @@
+mod common;
+
+use tokio_postgres::types::Type;
+use tokio_postgres_utils::{BoxError, FromRow, FromRowAccess, FromRowPlan, RowBatch, TryFromRow};
+
+#[derive(Debug, PartialEq, TryFromRow)]
+struct User {
+    #[column(skip)]
+    cache: Option<String>,
+    id: i32,
+    #[column(skip)]
+    seen: bool,
+    name: String,
+}
+
+#[derive(Debug, PartialEq, TryFromRow)]
+#[column(row_access)]
+struct Account {
+    id: i32,
+    #[column(rename = "login")]
+    name: String,
+    email: Option<String>,
+}
+
+#[test]
+fn skipped_fields_take_no_position() {
+    assert_eq!(<User as FromRow>::columns(), ["id", "name"]);
+}
+
+#[test]
+fn plan_finds_the_columns_read_by_name() -> Result<(), BoxError> {
+    let names = ["email", "extra", "login", "id"];
+    let plan = Account::column_plan_by_name(&names)?;
+    assert_eq!(plan.indices(), [3, 2, 0]);
+    let planned: Vec<&str> = plan.indices().iter().map(|&i| names[i]).collect();
+    assert_eq!(planned, <Account as FromRow>::columns());
+
+    let row = common::FakeRow::default()
+        .with("email", Type::TEXT, &None::<&str>)
+        .with("extra", Type::BOOL, &true)
+        .with("login", Type::TEXT, &"nur")
+        .with("id", Type::INT4, &7);
+    assert_eq!(Account::from_row_access(&row)?, Account { id: 7, name: "nur".into(), email: None });
+
+    // Both fail on a missing column.
+    let err = Account::column_plan_by_name(&["id", "email"]).err().unwrap();
+    assert_eq!(err.to_string(), "column `login` not found");
+    let row = common::FakeRow::default().with("id", Type::INT4, &7).with("email", Type::TEXT, &None::<&str>);
+    assert!(Account::from_row_access(&row).is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn plan_matches_decoding_by_name() -> Result<(), BoxError> {
+    let Some(client) = common::connect().await else {
+        return Ok(());
+    };
+    let rows = client.query("SELECT 'user ' || g AS name, g AS id FROM generate_series(1, 3) g", &[]).await?;
+    let plan = User::column_plan(rows[0].columns())?;
+    assert_eq!(plan.indices(), [1, 0]);
+    assert_eq!(
+        User::from_row_with_plan(&rows[0], &plan)?,
+        User { cache: None, id: 1, seen: false, name: "user 1".into() }
+    );
+
+    let planned = RowBatch::<User>::new(&rows).collect::<Result<Vec<_>, _>>()?;
+    let naive = rows.iter().map(User::try_from).collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(planned, naive);
+    assert_eq!(planned.len(), 3);
+    Ok(())
+}
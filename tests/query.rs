@@ -0,0 +1,74 @@
+mod common;
+
+use std::{future::Future, sync::Arc};
+use tokio_postgres::{Client, Row};
+use tokio_postgres_utils::{BoxError, ClientExt, FromRow};
+
+#[derive(Debug, PartialEq, FromRow)]
+struct User {
+    id: i32,
+}
+
+#[tokio::test]
+async fn query_as_with_row() -> Result<(), BoxError> {
+    let Some(client) = common::connect().await else {
+        return Ok(());
+    };
+    let rows: Vec<(User, Row)> = client
+        .query_as_with_row("SELECT g AS id, g * 10 AS score FROM generate_series(1, 2) g", ())
+        .await?;
+    let rows: Vec<(User, i32)> = rows.into_iter().map(|(user, row)| (user, row.get("score"))).collect();
+    assert_eq!(rows, [(User { id: 1 }, 10), (User { id: 2 }, 20)]);
+    Ok(())
+}
+
+fn assert_send<T: Send>(_: T) {}
+
+fn assert_spawnable<T: Future + Send + 'static>(_: T) {}
+
+/// Never called, only checks the `ClientExt` futures are `Send`, and `'static` when they own the client.
+#[allow(dead_code)]
+fn futures_are_send(client: Arc<Client>) {
+    assert_send(client.query_as::<User>("SELECT 1 AS id", ()));
+    assert_send(client.query_as_with_row::<User>("SELECT 1 AS id", (1,)));
+    assert_send(client.query_as_then("SELECT 1 AS id", (), |user: User| async move { Ok::<_, BoxError>(user.id) }));
+    assert_send(client.execute_returning_as::<User>("DELETE FROM users RETURNING id", ()));
+    assert_spawnable(async move { client.query_as::<User>("SELECT 1 AS id", ()).await });
+}
+
+#[tokio::test]
+async fn cancelled_query_leaves_client_usable() -> Result<(), BoxError> {
+    let Some(client) = common::connect().await else {
+        return Ok(());
+    };
+    let client = Arc::new(client);
+    let task = tokio::spawn({
+        let client = client.clone();
+        async move { client.query_as::<User>("SELECT 1 AS id FROM pg_sleep(10)", ()).await }
+    });
+    tokio::task::yield_now().await;
+    task.abort();
+    assert!(task.await.unwrap_err().is_cancelled());
+
+    let users = client.query_as::<User>("SELECT 2 AS id", ()).await?;
+    assert_eq!(users, [User { id: 2 }]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn execute_returning_as_maps_the_returned_row() -> Result<(), BoxError> {
+    let Some(client) = common::connect().await else {
+        return Ok(());
+    };
+    client.batch_execute("CREATE TEMP TABLE users (id int4, name text); INSERT INTO users VALUES (1, 'nur'), (2, 'bob'), (3, 'bob')").await?;
+    let sql = "UPDATE users SET name = $1 WHERE id = $2 RETURNING id";
+    assert_eq!(client.execute_returning_as::<User>(sql, ("ann", 1)).await?, Some(User { id: 1 }));
+    assert_eq!(client.execute_returning_as::<User>(sql, ("ann", 4)).await?, None);
+
+    // More than one returned row is an error, though the statement still ran.
+    let sql = "UPDATE users SET name = 'eve' WHERE name = 'bob' RETURNING id";
+    assert!(client.execute_returning_as::<User>(sql, ()).await.is_err());
+    let renamed: i64 = client.query_one("SELECT count(*) FROM users WHERE name = 'eve'", &[]).await?.get(0);
+    assert_eq!(renamed, 2);
+    Ok(())
+}
@@ -0,0 +1,12 @@
+// `TOKIO_PG_UTILS_RENAME_ALL` is read when the macros are built, so each case builds them again
+// with the variable set. `trybuild` runs cargo when the cases are dropped, and cargo inherits it.
+#[test]
+fn default_from_env() {
+    std::env::set_var("TOKIO_PG_UTILS_RENAME_ALL", "camelCase");
+    trybuild::TestCases::new().pass("tests/rename_all_default/camel_case.rs");
+
+    std::env::set_var("TOKIO_PG_UTILS_RENAME_ALL", "camel");
+    trybuild::TestCases::new().compile_fail("tests/rename_all_default/unknown_rule.rs");
+
+    std::env::remove_var("TOKIO_PG_UTILS_RENAME_ALL");
+}
@@ -0,0 +1,22 @@
+use tokio_postgres_utils::FromRow;
+
+#[derive(FromRow)]
+struct User {
+    user_id: i32,
+    display_name: String,
+}
+
+#[derive(FromRow)]
+#[column(rename_all = "SCREAMING_SNAKE_CASE")]
+struct Explicit {
+    user_id: i32,
+}
+
+#[derive(FromRow)]
+struct Tuple(i32, String);
+
+fn main() {
+    assert_eq!(User::mapped_columns(), ["userId", "displayName"]);
+    assert_eq!(Explicit::mapped_columns(), ["USER_ID"]);
+    let _ = Tuple::from_row;
+}
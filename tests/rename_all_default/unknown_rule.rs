@@ -0,0 +1,8 @@
+use tokio_postgres_utils::FromRow;
+
+#[derive(FromRow)]
+struct User {
+    user_id: i32,
+}
+
+fn main() {}
@@ -0,0 +1,60 @@
+mod common;
+
+use common::FakeRow;
+use std::cell::Cell;
+use tokio_postgres::types::{FromSql, Type};
+use tokio_postgres_utils::{BoxError, FromRowAccess, TryFromRow};
+
+thread_local! {
+    /// How many reads of `Flaky` fail before one succeeds.
+    static FAILURES: Cell<usize> = const { Cell::new(0) };
+    /// How many reads of `Flaky` were attempted.
+    static READS: Cell<usize> = const { Cell::new(0) };
+}
+
+#[derive(Debug, PartialEq)]
+struct Flaky(i32);
+
+impl<'a> FromSql<'a> for Flaky {
+    fn from_sql(ty: &Type, raw: &'a [u8]) -> Result<Self, BoxError> {
+        let read = READS.replace(READS.get() + 1);
+        if read < FAILURES.get() {
+            return Err(format!("read {read} failed").into());
+        }
+        i32::from_sql(ty, raw).map(Flaky)
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        <i32 as FromSql>::accepts(ty)
+    }
+}
+
+#[derive(Debug, PartialEq, TryFromRow)]
+#[column(row_access)]
+struct Reading {
+    #[column(retry = 3)]
+    value: Flaky,
+}
+
+fn read(failures: usize) -> Result<Reading, BoxError> {
+    FAILURES.set(failures);
+    READS.set(0);
+    Reading::from_row_access(&FakeRow::default().with("value", Type::INT4, &5))
+}
+
+#[test]
+fn retries_until_decoded() -> Result<(), BoxError> {
+    assert_eq!(read(0)?, Reading { value: Flaky(5) });
+    assert_eq!(READS.get(), 1);
+
+    assert_eq!(read(2)?, Reading { value: Flaky(5) });
+    assert_eq!(READS.get(), 3);
+    Ok(())
+}
+
+#[test]
+fn returns_last_error() {
+    let err = read(3).unwrap_err();
+    assert_eq!(READS.get(), 3);
+    assert!(err.to_string().contains("read 2 failed"), "{err}");
+}
@@ -0,0 +1,69 @@
+mod common;
+
+use std::sync::Arc;
+use tokio_postgres::types::Type;
+use tokio_postgres_utils::{assert_struct_matches_schema, validate_struct_against, BoxError, FromRow, SchemaIssue};
+
+// Only its columns and types are checked, it's never read.
+#[allow(dead_code)]
+#[derive(FromRow)]
+struct User {
+    id: i32,
+    #[column(rename = "display_name")]
+    name: String,
+    email: Option<String>,
+}
+
+#[tokio::test]
+async fn validate_struct_against_reads_the_table() -> Result<(), BoxError> {
+    let Some(client) = common::connect().await else {
+        return Ok(());
+    };
+    client
+        .batch_execute(
+            "CREATE TEMP TABLE matching_users (id int4, display_name text, email text, extra bool); \
+             CREATE TEMP TABLE drifted_users (id int8, display_name varchar)",
+        )
+        .await?;
+    assert!(validate_struct_against::<User>(&client, "matching_users").await?.is_ok());
+
+    let report = validate_struct_against::<User>(&client, "drifted_users").await?;
+    assert_eq!(
+        report.issues(),
+        [
+            SchemaIssue::TypeMismatch { column: "id", found: Type::INT8 },
+            SchemaIssue::MissingColumn { column: "email" },
+        ]
+    );
+
+    let report = validate_struct_against::<User>(&client, "no_such_table").await?;
+    assert_eq!(report.issues(), [SchemaIssue::TableNotFound]);
+    assert_eq!(report.to_string(), "table `no_such_table` not found");
+    Ok(())
+}
+
+#[tokio::test]
+async fn assert_struct_matches_schema_panics_on_drift() -> Result<(), BoxError> {
+    let Some(client) = common::connect().await else {
+        return Ok(());
+    };
+    client.batch_execute("CREATE TEMP TABLE asserted_users (id int4, display_name text, email text)").await?;
+    assert_struct_matches_schema!(User, &client, "asserted_users");
+
+    client.batch_execute("ALTER TABLE asserted_users DROP COLUMN email").await?;
+    let client = Arc::new(client);
+    let task = tokio::spawn({
+        let client = client.clone();
+        async move { assert_struct_matches_schema!(User, &*client, "asserted_users") }
+    });
+    let panic = task.await.unwrap_err().into_panic();
+    assert_eq!(
+        panic.downcast_ref::<String>().unwrap(),
+        "`User`: table `asserted_users` doesn't match: column `email` is missing"
+    );
+
+    let task = tokio::spawn(async move { assert_struct_matches_schema!(User, &*client, "no_such_table") });
+    let panic = task.await.unwrap_err().into_panic();
+    assert_eq!(panic.downcast_ref::<String>().unwrap(), "`User`: table `no_such_table` not found");
+    Ok(())
+}
@@ -0,0 +1,32 @@
+mod common;
+
+use tokio_postgres_utils::{BoxError, ToSqlEnum};
+
+#[derive(Debug, ToSqlEnum)]
+#[column(rename_all = "snake_case")]
+enum Mood {
+    Happy,
+    VeryHappy,
+    #[column(rename = "meh")]
+    Neutral,
+}
+
+#[tokio::test]
+async fn variants_are_written_as_their_labels() -> Result<(), BoxError> {
+    let Some(client) = common::connect().await else {
+        return Ok(());
+    };
+    client
+        .batch_execute(
+            "CREATE TYPE pg_temp.mood AS ENUM ('happy', 'very_happy', 'meh');
+             CREATE TEMP TABLE people (id serial, mood pg_temp.mood NOT NULL);",
+        )
+        .await?;
+    for mood in [Mood::Happy, Mood::VeryHappy, Mood::Neutral] {
+        client.execute("INSERT INTO people (mood) VALUES ($1)", &[&mood]).await?;
+    }
+    let rows = client.query("SELECT mood::text FROM people ORDER BY id", &[]).await?;
+    let moods: Vec<&str> = rows.iter().map(|row| row.get(0)).collect();
+    assert_eq!(moods, ["happy", "very_happy", "meh"]);
+    Ok(())
+}
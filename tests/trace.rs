@@ -0,0 +1,93 @@
+#![cfg(feature = "tracing")]
+
+mod common;
+
+use common::FakeRow;
+use std::{
+    fmt::Debug,
+    sync::{Arc, Mutex},
+};
+use tokio_postgres::types::Type;
+use tokio_postgres_utils::{BoxError, FromRowAccess, TryFromRow};
+use tracing::{
+    field::{Field, Visit},
+    span::{Attributes, Id, Record},
+    Subscriber,
+};
+use tracing_subscriber::{layer::Context, prelude::*, Layer};
+
+#[derive(Debug, PartialEq, TryFromRow)]
+#[column(row_access, trace)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+// Only checks the traced `try_from_row_with_ctx` compiles.
+#[allow(dead_code)]
+mod ctx {
+    use tokio_postgres_utils::TryFromRow;
+
+    struct Tenant;
+
+    fn tenant_column(_: &Tenant) -> String {
+        "acme_name".into()
+    }
+
+    #[derive(TryFromRow)]
+    #[column(ctx = Tenant, trace)]
+    struct User {
+        id: i32,
+        #[column(name_fn = tenant_column)]
+        name: String,
+    }
+}
+
+/// The name of a span, with the `Debug` form of each field recorded on it.
+type Span = (&'static str, Vec<(&'static str, String)>);
+
+/// Records every span, in the order they are created.
+#[derive(Clone, Default)]
+struct Spans(Arc<Mutex<Vec<Span>>>);
+
+struct Fields<'a>(&'a mut Vec<(&'static str, String)>);
+
+impl Visit for Fields<'_> {
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        self.0.push((field.name(), format!("{value:?}")));
+    }
+}
+
+impl<S: Subscriber> Layer<S> for Spans {
+    fn on_new_span(&self, attrs: &Attributes<'_>, _: &Id, _: Context<'_, S>) {
+        let mut fields = Vec::new();
+        attrs.record(&mut Fields(&mut fields));
+        self.0.lock().unwrap().push((attrs.metadata().name(), fields));
+    }
+
+    fn on_record(&self, _: &Id, values: &Record<'_>, _: Context<'_, S>) {
+        if let Some((_, fields)) = self.0.lock().unwrap().last_mut() {
+            values.record(&mut Fields(fields));
+        }
+    }
+}
+
+#[test]
+fn records_from_row_span() -> Result<(), BoxError> {
+    let spans = Spans::default();
+    let _guard = tracing_subscriber::registry().with(spans.clone()).set_default();
+
+    let row = FakeRow::default().with("x", Type::INT4, &1).with("y", Type::INT4, &2);
+    assert_eq!(Point::from_row_access(&row)?, Point { x: 1, y: 2 });
+
+    let spans = spans.0.lock().unwrap();
+    let [(name, fields)] = &spans[..] else {
+        panic!("expected one span, got {}", spans.len());
+    };
+    assert_eq!(*name, "from_row");
+    let names: Vec<_> = fields.iter().map(|(name, _)| *name).collect();
+    assert_eq!(names, ["type_name", "duration_us"]);
+    assert_eq!(fields[0].1, "\"Point\"");
+    assert!(fields[1].1.parse::<u64>().is_ok());
+    Ok(())
+}
@@ -0,0 +1,24 @@
+#![cfg(not(feature = "tracing"))]
+// Only the columns are checked, so the fields are never read.
+#![allow(dead_code)]
+
+use tokio_postgres_utils::{FromRow, TryFromRow};
+
+#[derive(FromRow)]
+#[column(trace)]
+struct User {
+    id: i32,
+}
+
+#[derive(TryFromRow)]
+#[column(trace, collect_errors)]
+struct Admin {
+    id: i32,
+}
+
+// Without the `tracing` feature, `#[column(trace)]` needs no `tracing` dependency and changes nothing.
+#[test]
+fn trace_changes_nothing_without_the_feature() {
+    assert_eq!(User::mapped_columns(), ["id"]);
+    assert_eq!(<Admin as FromRow>::columns(), ["id"]);
+}
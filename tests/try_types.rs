@@ -0,0 +1,48 @@
+mod common;
+
+use tokio_postgres::types::Type;
+use tokio_postgres_utils::{BoxError, FromRow, TryFromRow};
+
+#[derive(TryFromRow)]
+struct Sensor {
+    #[column(try_types(i32, &str))]
+    level: u8,
+}
+
+#[derive(TryFromRow)]
+struct Reading {
+    #[column(try_types(i64, String))]
+    value: i64,
+}
+
+#[derive(FromRow)]
+struct InfallibleReading {
+    #[column(try_types(i64, String))]
+    value: i64,
+}
+
+#[test]
+fn try_types_accept_any_of_the_types() {
+    assert_eq!(<Reading as FromRow>::accepts_column("value", &Type::INT8), Some(true));
+    assert_eq!(<Reading as FromRow>::accepts_column("value", &Type::TEXT), Some(true));
+    assert_eq!(<Reading as FromRow>::accepts_column("value", &Type::INT4), Some(false));
+}
+
+// A `try_types` field falls back to the next type when a decode or conversion fails.
+#[tokio::test]
+async fn try_types_fall_back_to_the_next_type() -> Result<(), BoxError> {
+    let Some(client) = common::connect().await else {
+        return Ok(());
+    };
+    let row = client.query_one("SELECT 7 AS level, '43'::text AS value", &[]).await?;
+    assert_eq!(Sensor::try_from(&row)?.level, 7);
+    assert_eq!(Reading::try_from(&row)?.value, 43);
+    assert_eq!(InfallibleReading::from(&row).value, 43);
+
+    // The `i32` doesn't fit in a `u8`, and the column isn't text either.
+    let row = client.query_one("SELECT 300 AS level", &[]).await?;
+    let err = Sensor::try_from(&row).err().unwrap().to_string();
+    assert!(err.contains("as `i32`: out of range integral type conversion attempted"), "{err}");
+    assert!(err.contains("as `&str`: error deserializing column"), "{err}");
+    Ok(())
+}
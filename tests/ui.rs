@@ -0,0 +1,24 @@
+#[test]
+fn ui() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/*.rs");
+    // A struct whose fields are all skipped is a warning, and an error with the `strict` feature.
+    if cfg!(feature = "strict") {
+        t.compile_fail("tests/ui/all_skipped/strict.rs");
+    } else {
+        t.pass("tests/ui/all_skipped/warns.rs");
+        t.compile_fail("tests/ui/all_skipped/denied_warning.rs");
+    }
+    // Attributes needing a feature are rejected without it.
+    if cfg!(feature = "uuid") {
+        t.compile_fail("tests/ui/features/uuid_wrong_type.rs");
+    } else {
+        t.compile_fail("tests/ui/features/no_uuid.rs");
+    }
+    if !cfg!(feature = "json") {
+        t.compile_fail("tests/ui/features/no_json.rs");
+    }
+    if !cfg!(feature = "base64") {
+        t.compile_fail("tests/ui/features/no_base64.rs");
+    }
+}
@@ -0,0 +1,11 @@
+#![deny(deprecated)]
+
+use tokio_postgres_utils::FromRow;
+
+#[derive(FromRow)]
+struct User {
+    #[column(skip)]
+    name: String,
+}
+
+fn main() {}
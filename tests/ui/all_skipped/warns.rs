@@ -0,0 +1,9 @@
+use tokio_postgres_utils::FromRow;
+
+#[derive(FromRow)]
+struct User {
+    #[column(skip)]
+    name: String,
+}
+
+fn main() {}
@@ -0,0 +1,7 @@
+use tokio_postgres_utils::TryFromRow;
+
+#[derive(Default, TryFromRow)]
+#[column(base_default)]
+struct Pair(i32, #[column(skip)] u32);
+
+fn main() {}
@@ -0,0 +1,14 @@
+use tokio_postgres_utils::FromRow;
+
+#[derive(FromRow)]
+struct Address {
+    city: String,
+}
+
+#[derive(FromRow)]
+struct User {
+    #[column(flatten, rename = "x")]
+    address: Address,
+}
+
+fn main() {}
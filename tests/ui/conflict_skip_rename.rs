@@ -0,0 +1,9 @@
+use tokio_postgres_utils::FromRow;
+
+#[derive(FromRow)]
+struct User {
+    #[column(skip, rename = "x")]
+    name: String,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use tokio_postgres_utils::FromRow;
+
+#[derive(FromRow)]
+struct User {
+    #[column(skip)]
+    #[column(rename = "x")]
+    name: String,
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use tokio_postgres_utils::FromRow;
+
+struct Tenant;
+
+// There is no `try_from_row_with_ctx` for `FromRow`.
+#[derive(FromRow)]
+#[column(ctx = Tenant)]
+struct User {
+    name: String,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use tokio_postgres_utils::FromRow;
+
+#[derive(FromRow)]
+struct Handler {
+    id: i32,
+    callback: fn(),
+}
+
+fn main() {}
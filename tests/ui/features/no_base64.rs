@@ -0,0 +1,9 @@
+use tokio_postgres_utils::TryFromRow;
+
+#[derive(TryFromRow)]
+struct Avatar {
+    #[column(bytea_base64)]
+    image: String,
+}
+
+fn main() {}
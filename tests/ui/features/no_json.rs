@@ -0,0 +1,9 @@
+use tokio_postgres_utils::TryFromRow;
+
+#[derive(TryFromRow)]
+struct Post {
+    #[column(json)]
+    comments: Vec<(i32, String)>,
+}
+
+fn main() {}
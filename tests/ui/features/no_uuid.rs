@@ -0,0 +1,9 @@
+use tokio_postgres_utils::TryFromRow;
+
+#[derive(TryFromRow)]
+struct Session {
+    #[column(uuid)]
+    id: [u8; 16],
+}
+
+fn main() {}
@@ -0,0 +1,15 @@
+use tokio_postgres_utils::FromRow;
+
+#[derive(FromRow)]
+#[column(lenient = "yes")]
+struct User {
+    id: i32,
+}
+
+#[derive(FromRow)]
+#[column(case_insensitive(true))]
+struct Admin {
+    id: i32,
+}
+
+fn main() {}
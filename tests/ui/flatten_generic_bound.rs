@@ -0,0 +1,15 @@
+use tokio_postgres_utils::TryFromRow;
+
+#[derive(TryFromRow)]
+struct Repo<Id> {
+    id: Id,
+}
+
+// The bound of a flattened generic type is checked at the field.
+#[derive(TryFromRow)]
+struct Listing {
+    #[column(flatten)]
+    repo: Repo<fn()>,
+}
+
+fn main() {}
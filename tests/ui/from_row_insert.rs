@@ -0,0 +1,10 @@
+use tokio_postgres_utils::Insert;
+
+// `Insert` doesn't know which columns the function reads.
+#[derive(Insert)]
+struct User {
+    #[column(from_row = |r: &tokio_postgres::Row| r.get(0))]
+    name: String,
+}
+
+fn main() {}
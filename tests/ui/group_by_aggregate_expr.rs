@@ -0,0 +1,10 @@
+use tokio_postgres_utils::FromRow;
+
+// An aggregate must be a plain function name.
+#[derive(FromRow)]
+struct Totals {
+    #[column(aggregate = "sum(1); DROP TABLE sales; --")]
+    total: i64,
+}
+
+fn main() {}
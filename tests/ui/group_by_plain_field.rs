@@ -0,0 +1,11 @@
+use tokio_postgres_utils::FromRow;
+
+// A field that is neither grouped nor aggregated can't be selected.
+#[derive(FromRow)]
+struct SalesByRegion {
+    #[column(group_key)]
+    region: String,
+    total: i64,
+}
+
+fn main() {}
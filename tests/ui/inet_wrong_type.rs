@@ -0,0 +1,9 @@
+use tokio_postgres_utils::TryFromRow;
+
+#[derive(TryFromRow)]
+struct Visit {
+    #[column(inet)]
+    client: String,
+}
+
+fn main() {}
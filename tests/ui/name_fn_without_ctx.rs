@@ -0,0 +1,9 @@
+use tokio_postgres_utils::TryFromRow;
+
+#[derive(TryFromRow)]
+struct User {
+    #[column(name_fn = |_: &()| "name".to_owned())]
+    name: String,
+}
+
+fn main() {}
@@ -0,0 +1,12 @@
+use std::{collections::HashMap, sync::LazyLock};
+use tokio_postgres_utils::TryFromRow;
+
+static NAMES: LazyLock<HashMap<&str, &str>> = LazyLock::new(HashMap::new);
+
+#[derive(TryFromRow)]
+#[column(lenient, name_map = NAMES)]
+struct Sales {
+    total: i32,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use tokio_postgres_utils::FromRow;
+
+#[derive(FromRow)]
+struct User {
+    #[column(ordinal = 1i32)]
+    name: String,
+}
+
+fn main() {}
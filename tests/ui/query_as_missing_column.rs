@@ -0,0 +1,15 @@
+use tokio_postgres_utils::{query_as, BoxError, FromRow};
+
+#[derive(FromRow)]
+struct User {
+    id: i32,
+    #[column(rename = "display_name")]
+    name: String,
+}
+
+async fn read(client: &tokio_postgres::Client) -> Result<(), BoxError> {
+    let _: Vec<User> = query_as!(client, User, "SELECT id, name FROM users").await?;
+    Ok(())
+}
+
+fn main() {}
@@ -0,0 +1,7 @@
+use tokio_postgres_utils::FromRow;
+
+#[derive(FromRow)]
+#[column(rename_all = "camelCase")]
+struct Point(i32, i32);
+
+fn main() {}
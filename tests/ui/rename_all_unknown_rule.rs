@@ -0,0 +1,9 @@
+use tokio_postgres_utils::FromRow;
+
+#[derive(FromRow)]
+#[column(rename_all = "Title Case")]
+struct User {
+    user_id: i32,
+}
+
+fn main() {}
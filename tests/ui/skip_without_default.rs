@@ -0,0 +1,19 @@
+use tokio_postgres_utils::{FromRow, TryFromRow};
+
+struct Cache;
+
+#[derive(FromRow)]
+struct User {
+    name: String,
+    #[column(skip)]
+    cache: Cache,
+}
+
+#[derive(TryFromRow)]
+struct Admin {
+    name: String,
+    #[column(skip)]
+    cache: Cache,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use tokio_postgres_utils::FromRow;
+
+#[derive(FromRow)]
+#[column(strip_prefix = "usr_")]
+struct User {
+    id: i32,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use tokio_postgres_utils::TryFromRow;
+
+#[derive(TryFromRow)]
+struct Reading {
+    #[column(try_types())]
+    value: i64,
+}
+
+fn main() {}
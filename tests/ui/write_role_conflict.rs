@@ -0,0 +1,20 @@
+use tokio_postgres_utils::{FromRow, Insert};
+
+#[derive(FromRow, Insert)]
+struct User {
+    #[column(key, skip_insert)]
+    id: i32,
+}
+
+#[derive(FromRow)]
+struct Address {
+    city: String,
+}
+
+#[derive(FromRow)]
+struct Account {
+    #[column(flatten, key)]
+    address: Address,
+}
+
+fn main() {}
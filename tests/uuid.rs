@@ -0,0 +1,70 @@
+#![cfg(feature = "uuid")]
+
+mod common;
+
+use tokio_postgres::types::Type;
+use tokio_postgres_utils::{BoxError, ClientExt, Insert, TryFromRow};
+use uuid::Uuid;
+
+#[derive(TryFromRow, Insert)]
+struct Session {
+    #[column(uuid)]
+    id: Uuid,
+    #[column(uuid)]
+    parent: Option<Uuid>,
+}
+
+#[test]
+fn uuid_fields_are_bound_as_uuid() {
+    assert_eq!(Session::PARAM_TYPES, [Some(Type::UUID), Some(Type::UUID)]);
+}
+
+#[tokio::test]
+async fn uuid_columns_read_into_uuid() -> Result<(), BoxError> {
+    let Some(client) = common::connect().await else {
+        return Ok(());
+    };
+    let sessions: Vec<Session> = client
+        .query_as("SELECT '67e55044-10b1-426f-9247-bb680e5fe0c8'::uuid AS id, NULL::uuid AS parent", ())
+        .await?;
+    assert_eq!(sessions[0].id, Uuid::from_u128(0x67e5_5044_10b1_426f_9247_bb68_0e5f_e0c8));
+    assert_eq!(sessions[0].parent, None);
+    Ok(())
+}
+
+// `key` and `skip_insert` only change how the field is written, so they combine with `uuid`.
+#[derive(TryFromRow, Insert)]
+struct Device {
+    #[column(key, uuid)]
+    id: Uuid,
+    #[column(skip_insert, uuid)]
+    owner: Option<Uuid>,
+    name: String,
+}
+
+#[test]
+fn uuid_combines_with_write_roles() {
+    assert_eq!(Device::COLUMNS, ["id", "name"]);
+    assert_eq!(Device::PARAM_TYPES, [Some(Type::UUID), Some(Type::TEXT)]);
+    assert_eq!(
+        Device::upsert_sql("devices"),
+        "INSERT INTO devices (id, name) VALUES ($1, $2) ON CONFLICT (id) DO UPDATE SET name = EXCLUDED.name"
+    );
+    assert_eq!(<Device as tokio_postgres_utils::FromRow>::columns(), ["id", "owner", "name"]);
+}
+
+#[tokio::test]
+async fn uuid_key_reads_none_for_a_null_key() -> Result<(), BoxError> {
+    let Some(client) = common::connect().await else {
+        return Ok(());
+    };
+    let row = client
+        .query_one("SELECT '67e55044-10b1-426f-9247-bb680e5fe0c8'::uuid AS id, NULL::uuid AS owner, 'phone' AS name", &[])
+        .await?;
+    let device = Device::try_from_row_opt(&row)?.unwrap();
+    assert_eq!((device.id.as_u128(), device.owner, device.name.as_str()), (0x67e5_5044_10b1_426f_9247_bb68_0e5f_e0c8, None, "phone"));
+
+    let row = client.query_one("SELECT NULL::uuid AS id, NULL::uuid AS owner, NULL::text AS name", &[]).await?;
+    assert!(Device::try_from_row_opt(&row)?.is_none());
+    Ok(())
+}
@@ -0,0 +1,4 @@
+fn main() {
+    // `rename_all` default, read with `option_env!`.
+    println!("cargo:rerun-if-env-changed=TOKIO_PG_UTILS_RENAME_ALL");
+}
@@ -0,0 +1,667 @@
+use crate::case::RenameRule;
+use quote2::proc_macro2::{Ident, Literal, Span, TokenStream, TokenTree};
+use syn::{ext::IdentExt, parse::Parser, spanned::Spanned, *};
+
+/// Field level `#[column(..)]` attributes, merged from every `#[column]` on the field.
+pub struct ColumnAttr {
+    pub kind: ColumnKind,
+    /// The part the field plays in writes, independent of how it's read.
+    pub write: Option<WriteRole>,
+    pub rename: Option<Rename>,
+    /// Transforms the field before `Insert` binds it, the counterpart of `with` for writes.
+    pub write_with: Option<Box<Expr>>,
+    /// Reads the `nth` column (from 0) with the field's column name, for rows where several share it.
+    pub nth: Option<usize>,
+    /// Reads the column at this position (from 0), bypassing the field's column name and every rename.
+    pub ordinal: Option<usize>,
+    /// Computes the column name from the context of `try_from_row_with_ctx`.
+    pub name_fn: Option<Box<Expr>>,
+    /// The part the column plays in `group_by_sql` and `select_expr_sql`.
+    pub grouping: Option<Grouping>,
+    /// How many times `TryFromRow` tries to decode the column before returning the error.
+    pub retry: Option<usize>,
+    /// A `flatten` field whose columns may overlap the earlier fields' under `strict_flatten`.
+    pub overrides: bool,
+    /// Declaration position of the field, substituted for `{index}` by `rename_fmt`.
+    pub index: usize,
+}
+
+/// How a field of a reporting struct is selected.
+pub enum Grouping {
+    /// A `GROUP BY` column, selected as is.
+    Key,
+    /// A column selected through an aggregate function, such as `SUM`, written in upper case.
+    Aggregate(String),
+}
+
+/// How a field is written by `Insert` and `PartialUpdate`, on top of how its `ColumnKind` reads it.
+pub enum WriteRole {
+    /// Identifies the row, in the conflict target of `upsert_sql`, the `WHERE` clause of `PartialUpdate` and `try_from_row_opt`.
+    Key,
+    /// Read but never inserted, e.g. a column the database fills in.
+    SkipInsert,
+}
+
+/// How a field is read.
+pub enum ColumnKind {
+    Skip,
+    Flatten,
+    /// Moves the whole owned row into the field, through its `From<Row>` or `TryFrom<Row>` impl.
+    FlattenOwned,
+    FlattenRest,
+    ArrayOfComposite,
+    ByteaBase64,
+    InfallibleGet,
+    Decimal,
+    Inet,
+    Uuid,
+    /// Reads the column as the parameter of a function or closure, and stores what it returns.
+    With(Box<Expr>),
+    /// Reads a text column and parses it into the field type with `FromStr`.
+    FromStr,
+    /// Reads a `json` or `jsonb` column and deserializes it into the field type with `serde_json`.
+    Json,
+    /// Computes the field from the whole row with a function taking `&Row`.
+    FromRow(Box<Expr>),
+    /// Decodes the column as each of the types in order, converting the first that succeeds into the field type.
+    TryTypes(Vec<Type>),
+    /// Reads one `child` per row, grouped under the parent with the same `group_by` column by `group_and_map`.
+    HasMany { group_by: Literal, child: Box<Type> },
+    None,
+}
+
+impl ColumnKind {
+    /// Whether reading the field can fail with other errors than `tokio_postgres::Error`, which `TryFromRow` then boxes.
+    pub fn boxes_error(&self) -> bool {
+        matches!(
+            self,
+            ColumnKind::Flatten | ColumnKind::ArrayOfComposite | ColumnKind::HasMany { .. } | ColumnKind::FromStr | ColumnKind::Json | ColumnKind::FromRow(_) | ColumnKind::TryTypes(_)
+        )
+    }
+}
+
+/// Overrides the column name of a field.
+pub enum Rename {
+    Name(Literal),
+    Case(RenameRule),
+}
+
+/// Build-time `rename_all` default for structs with named fields that don't set one.
+const DEFAULT_RENAME_ALL: Option<&str> = option_env!("TOKIO_PG_UTILS_RENAME_ALL");
+
+/// Struct level `#[column(..)]` attributes.
+#[derive(Default)]
+pub struct ContainerAttr {
+    pub rename_all: Option<RenameRule>,
+    pub rename_fmt: Option<String>,
+    /// Prepended to the default column name of every field, after `rename_all` and `rename_fmt`.
+    pub column_prefix: Option<String>,
+    pub composite: bool,
+    pub lenient: bool,
+    pub case_insensitive: bool,
+    pub strict_flatten: bool,
+    pub base_default: bool,
+    pub row_access: bool,
+    pub quote_identifiers: bool,
+    pub strip_trailing_underscore: bool,
+    pub binary_copy: bool,
+    pub collect_errors: bool,
+    pub trace: bool,
+    pub table: Option<Literal>,
+    pub from_column: Option<Literal>,
+    pub from_json_column: Option<Literal>,
+    pub builder: Option<Path>,
+    /// A `HashMap<&str, &str>` from field names to column names, looked up at runtime.
+    pub name_map: Option<Path>,
+    /// The type of the context `try_from_row_with_ctx` passes to the `name_fn` of the fields.
+    pub ctx: Option<Path>,
+    pub repr_enum: Option<Literal>,
+}
+
+impl ContainerAttr {
+    pub fn new(attrs: &[Attribute], fields: &Fields) -> Result<Self> {
+        let mut container = ContainerAttr::default();
+        for tokens in attrs.iter().filter_map(column_tokens) {
+            for item in items(tokens) {
+                let mut tokens = item.into_iter();
+                let Some(TokenTree::Ident(key)) = tokens.next() else {
+                    continue;
+                };
+                if key == "rename_all" {
+                    if let Fields::Unnamed(_) = fields {
+                        return Err(Error::new(
+                            key.span(),
+                            "`rename_all` is meaningless for tuple structs, their fields are read by position",
+                        ));
+                    }
+                    let lit = value(&mut tokens, &key)?;
+                    container.rename_all = Some(rename_rule(&lit)?);
+                } else if key == "rename_fmt" {
+                    named_fields(fields, &key)?;
+                    let lit = value(&mut tokens, &key)?;
+                    container.rename_fmt = Some(rename_fmt(&lit)?);
+                } else if key == "column_prefix" {
+                    named_fields(fields, &key)?;
+                    let lit = value(&mut tokens, &key)?;
+                    container.column_prefix = Some(lit_str(&lit)?);
+                } else if key == "strip_prefix" {
+                    return Err(Error::new(key.span(), "`strip_prefix` was renamed to `column_prefix`"));
+                } else if key == "composite" {
+                    container.composite = flag(&mut tokens, &key)?;
+                } else if key == "lenient" {
+                    container.lenient = flag(&mut tokens, &key)?;
+                } else if key == "case_insensitive" {
+                    container.case_insensitive = flag(&mut tokens, &key)?;
+                } else if key == "strict_flatten" {
+                    container.strict_flatten = flag(&mut tokens, &key)?;
+                } else if key == "base_default" {
+                    named_fields(fields, &key)?;
+                    container.base_default = flag(&mut tokens, &key)?;
+                } else if key == "builder" {
+                    if let Fields::Unit = fields {
+                        return Err(Error::new(key.span(), "`builder` requires a struct with fields"));
+                    }
+                    container.builder = Some(path_value(tokens, &key)?);
+                } else if key == "name_map" {
+                    named_fields(fields, &key)?;
+                    container.name_map = Some(path_value(tokens, &key)?);
+                } else if key == "ctx" {
+                    named_fields(fields, &key)?;
+                    container.ctx = Some(path_value(tokens, &key)?);
+                } else if key == "row_access" {
+                    named_fields(fields, &key)?;
+                    container.row_access = flag(&mut tokens, &key)?;
+                } else if key == "quote_identifiers" {
+                    container.quote_identifiers = flag(&mut tokens, &key)?;
+                } else if key == "strip_trailing_underscore" {
+                    container.strip_trailing_underscore = flag(&mut tokens, &key)?;
+                } else if key == "binary_copy" {
+                    container.binary_copy = flag(&mut tokens, &key)?;
+                } else if key == "collect_errors" {
+                    container.collect_errors = flag(&mut tokens, &key)?;
+                } else if key == "trace" {
+                    container.trace = flag(&mut tokens, &key)?;
+                } else if key == "table" {
+                    let lit = value(&mut tokens, &key)?;
+                    lit_str(&lit)?;
+                    container.table = Some(lit);
+                } else if key == "repr_enum" {
+                    let lit = value(&mut tokens, &key)?;
+                    lit_str(&lit)?;
+                    container.repr_enum = Some(lit);
+                } else if key == "from_column" {
+                    let lit = value(&mut tokens, &key)?;
+                    lit_str(&lit)?;
+                    container.from_column = Some(lit);
+                } else if key == "from_json_column" {
+                    let lit = value(&mut tokens, &key)?;
+                    lit_str(&lit)?;
+                    container.from_json_column = Some(lit);
+                }
+            }
+        }
+        if let (true, Some(builder)) = (container.base_default, &container.builder) {
+            return Err(Error::new_spanned(builder, "`base_default` and `builder` cannot be combined, the builder sets every skipped field"));
+        }
+        if let (true, Some(map)) = (container.lenient || container.case_insensitive, &container.name_map) {
+            let attr_name = if container.lenient { "lenient" } else { "case_insensitive" };
+            return Err(Error::new_spanned(map, format!("`{attr_name}` can't be combined with `name_map`")));
+        }
+        if let (None, Some(rule), Fields::Named(_)) = (container.rename_all, DEFAULT_RENAME_ALL, fields) {
+            let rule = RenameRule::parse(rule).ok_or_else(|| {
+                Error::new(
+                    Span::call_site(),
+                    format!("unknown rename rule in `TOKIO_PG_UTILS_RENAME_ALL`, expected one of {}", RenameRule::VARIANTS),
+                )
+            })?;
+            container.rename_all = Some(rule);
+        }
+        Ok(container)
+    }
+}
+
+pub fn column_attr(attrs: &[Attribute]) -> Result<ColumnAttr> {
+    let mut kind: Option<(Ident, ColumnKind)> = None;
+    let mut write: Option<(Ident, WriteRole)> = None;
+    let mut rename: Option<(Ident, Rename)> = None;
+    let mut write_with: Option<(Ident, Expr)> = None;
+    let mut nth: Option<(Ident, usize)> = None;
+    let mut ordinal: Option<(Ident, usize)> = None;
+    let mut name_fn: Option<(Ident, Expr)> = None;
+    let mut grouping: Option<(Ident, Grouping)> = None;
+    let mut retry: Option<(Ident, usize)> = None;
+    let mut overrides: Option<Ident> = None;
+    for tokens in attrs.iter().filter_map(column_tokens) {
+        for item in items(tokens) {
+            let mut tokens = item.into_iter();
+            let Some(TokenTree::Ident(key)) = tokens.next() else {
+                continue;
+            };
+            let new_kind = match key.to_string().as_str() {
+                "skip" => ColumnKind::Skip,
+                "flatten" => ColumnKind::Flatten,
+                "flatten_owned" => ColumnKind::FlattenOwned,
+                "flatten_rest" => ColumnKind::FlattenRest,
+                "array_of_composite" => ColumnKind::ArrayOfComposite,
+                "bytea_base64" if !cfg!(feature = "base64") => {
+                    return Err(Error::new(key.span(), "`bytea_base64` requires the `base64` feature of `tokio-postgres-utils`"));
+                }
+                "bytea_base64" => ColumnKind::ByteaBase64,
+                "infallible_get" => ColumnKind::InfallibleGet,
+                "decimal" => ColumnKind::Decimal,
+                "inet" => ColumnKind::Inet,
+                "uuid" => ColumnKind::Uuid,
+                "from_str" => ColumnKind::FromStr,
+                "json" => ColumnKind::Json,
+                "with" => ColumnKind::With(Box::new(with_value(&mut tokens, &key)?)),
+                "from_row" => ColumnKind::FromRow(Box::new(expr_value(&mut tokens, &key)?)),
+                "has_many" => has_many(&mut tokens, &key)?,
+                "try_types" => try_types(&mut tokens, &key)?,
+                "key" | "skip_insert" => {
+                    if let Some((prev, _)) = &write {
+                        return Err(conflict(prev, &key));
+                    }
+                    let role = if key == "key" { WriteRole::Key } else { WriteRole::SkipInsert };
+                    write = Some((key, role));
+                    continue;
+                }
+                "override" => {
+                    if let Some(prev) = &overrides {
+                        return Err(conflict(prev, &key));
+                    }
+                    overrides = Some(key);
+                    continue;
+                }
+                "nth" => {
+                    if let Some((prev, _)) = &nth {
+                        return Err(conflict(prev, &key));
+                    }
+                    let lit = value(&mut tokens, &key)?;
+                    let Lit::Int(int) = Lit::new(lit) else {
+                        return Err(Error::new(key.span(), "expected `nth = <integer>`"));
+                    };
+                    nth = Some((key, int.base10_parse()?));
+                    continue;
+                }
+                "retry" => {
+                    if let Some((prev, _)) = &retry {
+                        return Err(conflict(prev, &key));
+                    }
+                    let lit = value(&mut tokens, &key)?;
+                    let Lit::Int(int) = Lit::new(lit) else {
+                        return Err(Error::new(key.span(), "expected `retry = <integer>`"));
+                    };
+                    let attempts = int.base10_parse()?;
+                    if attempts == 0 {
+                        return Err(Error::new_spanned(int, "`retry` needs at least one attempt"));
+                    }
+                    retry = Some((key, attempts));
+                    continue;
+                }
+                "ordinal" => {
+                    if let Some((prev, _)) = &ordinal {
+                        return Err(conflict(prev, &key));
+                    }
+                    let index = ordinal_value(&mut tokens, &key)?;
+                    ordinal = Some((key, index));
+                    continue;
+                }
+                "group_key" | "aggregate" => {
+                    if let Some((prev, _)) = &grouping {
+                        return Err(conflict(prev, &key));
+                    }
+                    let value = if key == "group_key" {
+                        Grouping::Key
+                    } else {
+                        let function = lit_str(&value(&mut tokens, &key)?)?;
+                        if function.is_empty() || !function.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                            return Err(Error::new(key.span(), "`aggregate` must name a SQL function, e.g. `aggregate = \"sum\"`"));
+                        }
+                        Grouping::Aggregate(function.to_uppercase())
+                    };
+                    grouping = Some((key, value));
+                    continue;
+                }
+                "name_fn" => {
+                    if let Some((prev, _)) = &name_fn {
+                        return Err(conflict(prev, &key));
+                    }
+                    let expr = expr_value(&mut tokens, &key)?;
+                    name_fn = Some((key, expr));
+                    continue;
+                }
+                "write_with" => {
+                    if let Some((prev, _)) = &write_with {
+                        return Err(conflict(prev, &key));
+                    }
+                    let expr = expr_value(&mut tokens, &key)?;
+                    write_with = Some((key, expr));
+                    continue;
+                }
+                "rename" | "rename_case" => {
+                    if let Some((prev, _)) = &rename {
+                        return Err(conflict(prev, &key));
+                    }
+                    let lit = value(&mut tokens, &key)?;
+                    let value = if key == "rename" {
+                        Rename::Name(lit)
+                    } else {
+                        Rename::Case(rename_rule(&lit)?)
+                    };
+                    rename = Some((key, value));
+                    continue;
+                }
+                _ => continue,
+            };
+            if let Some((prev, _)) = &kind {
+                return Err(conflict(prev, &key));
+            }
+            kind = Some((key, new_kind));
+        }
+    }
+    // A field without a column of its own can't name, position, group or write one.
+    if let Some((kind, ColumnKind::Skip | ColumnKind::Flatten | ColumnKind::FlattenOwned | ColumnKind::FlattenRest | ColumnKind::HasMany { .. } | ColumnKind::FromRow(_))) = &kind {
+        let others = [attr_key(&rename), attr_key(&nth), attr_key(&ordinal), attr_key(&name_fn), attr_key(&grouping), attr_key(&write)];
+        if let Some(other) = others.into_iter().flatten().next() {
+            return Err(conflict(kind, other));
+        }
+    }
+    if let (Some((skip_insert, WriteRole::SkipInsert)), Some((write_with, _))) = (&write, &write_with) {
+        return Err(conflict(skip_insert, write_with));
+    }
+    if let (Some((kind, ColumnKind::Skip)), Some((write_with, _))) = (&kind, &write_with) {
+        return Err(conflict(kind, write_with));
+    }
+    // Only a column read with a single `try_get` can be decoded again.
+    if let (Some((kind, kind_value)), Some((retry, _))) = (&kind, &retry) {
+        if !matches!(kind_value, ColumnKind::With(_)) {
+            return Err(conflict(kind, retry));
+        }
+    }
+    if let (Some((grouping, _)), Some((ordinal, _))) = (&grouping, &ordinal) {
+        return Err(conflict(grouping, ordinal));
+    }
+    if let (Some(name_fn), Some(other)) = (attr_key(&name_fn), attr_key(&ordinal).or(attr_key(&nth))) {
+        return Err(conflict(name_fn, other));
+    }
+    // An ordinal field is found by position alone, so no column name applies to it.
+    if let (Some(ordinal), Some(other)) = (attr_key(&ordinal), attr_key(&rename).or(attr_key(&nth))) {
+        return Err(conflict(ordinal, other));
+    }
+    if let (false, Some(overrides)) = (matches!(kind, Some((_, ColumnKind::Flatten))), &overrides) {
+        return Err(Error::new(overrides.span(), "`override` only applies to `flatten` fields"));
+    }
+    Ok(ColumnAttr {
+        kind: kind.map_or(ColumnKind::None, |(_, kind)| kind),
+        write: write.map(|(_, role)| role),
+        rename: rename.map(|(_, rename)| rename),
+        write_with: write_with.map(|(_, expr)| Box::new(expr)),
+        nth: nth.map(|(_, nth)| nth),
+        ordinal: ordinal.map(|(_, ordinal)| ordinal),
+        name_fn: name_fn.map(|(_, expr)| Box::new(expr)),
+        grouping: grouping.map(|(_, grouping)| grouping),
+        retry: retry.map(|(_, attempts)| attempts),
+        overrides: overrides.is_some(),
+        index: 0,
+    })
+}
+
+/// The key of a field attribute parsed along with its value.
+fn attr_key<T>(attr: &Option<(Ident, T)>) -> Option<&Ident> {
+    attr.as_ref().map(|(key, _)| key)
+}
+
+/// Fails unless the container attribute `key` is on a struct with named fields.
+fn named_fields(fields: &Fields, key: &Ident) -> Result<()> {
+    match fields {
+        Fields::Named(_) => Ok(()),
+        _ => Err(Error::new(key.span(), format!("`{key}` requires a struct with named fields"))),
+    }
+}
+
+fn conflict(prev: &Ident, key: &Ident) -> Error {
+    if prev == key {
+        return Error::new(key.span(), format!("duplicate `{key}` attribute"));
+    }
+    let reason = if prev == "skip" || key == "skip" {
+        ", a skipped field never reads a column"
+    } else if prev == "flatten" || key == "flatten" {
+        ", a flattened field reads its columns through its own type"
+    } else {
+        ""
+    };
+    Error::new(key.span(), format!("`{prev}` and `{key}` cannot be combined{reason}"))
+}
+
+/// Splits the tokens of an attribute into its comma separated items.
+fn items(tokens: TokenStream) -> Vec<Vec<TokenTree>> {
+    let mut items = vec![Vec::new()];
+    for token in tokens {
+        match token {
+            TokenTree::Punct(p) if p.as_char() == ',' => items.push(Vec::new()),
+            token => items.last_mut().unwrap().push(token),
+        }
+    }
+    items
+}
+
+/// Resolves the column name a field reads, or `None` if it doesn't read a column by name.
+pub fn column_name(name: &Ident, attr: &ColumnAttr, container: &ContainerAttr) -> Option<Literal> {
+    let mut name = name.unraw().to_string();
+    if container.strip_trailing_underscore && name.len() > 1 && name.ends_with('_') {
+        name.pop();
+    }
+    if attr.ordinal.is_some()
+        || matches!(
+            attr.kind,
+            ColumnKind::Skip
+                | ColumnKind::Flatten
+                | ColumnKind::FlattenOwned
+                | ColumnKind::FlattenRest
+                | ColumnKind::HasMany { .. }
+                | ColumnKind::FromRow(_)
+        )
+    {
+        return None;
+    }
+    let rule = match &attr.rename {
+        Some(Rename::Name(rename)) => return Some(rename.clone()),
+        Some(Rename::Case(rule)) => Some(*rule),
+        None => container.rename_all,
+    };
+    let name = match rule {
+        Some(rule) => rule.apply(&name),
+        None => name,
+    };
+    let name = match &container.rename_fmt {
+        Some(fmt) => fmt.replace("{index}", &attr.index.to_string()).replace("{name}", &name),
+        None => name,
+    };
+    Some(Literal::string(&match &container.column_prefix {
+        Some(prefix) => format!("{prefix}{name}"),
+        None => name,
+    }))
+}
+
+/// The column name of a field as written in generated SQL, double-quoted with `quote_identifiers`.
+pub fn sql_column(name: &Ident, attr: &ColumnAttr, container: &ContainerAttr) -> Option<String> {
+    let column = lit_str(&column_name(name, attr, container)?).ok()?;
+    Some(if container.quote_identifiers { quote_ident(&column) } else { column })
+}
+
+/// Double-quotes a SQL identifier, doubling the quotes inside it.
+pub fn quote_ident(ident: &str) -> String {
+    format!("\"{}\"", ident.replace('"', "\"\""))
+}
+
+fn column_tokens(attr: &Attribute) -> Option<TokenStream> {
+    match &attr.meta {
+        Meta::List(MetaList { path, tokens, .. }) if path.is_ident("column") => Some(tokens.clone()),
+        _ => None,
+    }
+}
+
+/// Parses the `= "literal"` part of a `key = "literal"` pair.
+fn value(tokens: &mut impl Iterator<Item = TokenTree>, key: &Ident) -> Result<Literal> {
+    if matches!(tokens.next(), Some(TokenTree::Punct(p)) if p.as_char() == '=') {
+        if let Some(TokenTree::Literal(lit)) = tokens.next() {
+            return Ok(lit);
+        }
+    }
+    Err(Error::new(key.span(), format!("expected `{key} = \"...\"`")))
+}
+
+/// Parses a flag, on when given alone or as `key = true`, and off as `key = false`.
+fn flag(tokens: &mut impl Iterator<Item = TokenTree>, key: &Ident) -> Result<bool> {
+    let Some(eq) = tokens.next() else {
+        return Ok(true);
+    };
+    match (eq, tokens.next(), tokens.next()) {
+        (TokenTree::Punct(eq), Some(TokenTree::Ident(value)), None) if eq.as_char() == '=' && (value == "true" || value == "false") => {
+            Ok(value == "true")
+        }
+        _ => Err(Error::new(key.span(), format!("expected `{key}` or `{key} = <bool>`"))),
+    }
+}
+
+/// Parses the `(group_by = "..", child = Type)` part of `has_many`.
+fn has_many(tokens: &mut impl Iterator<Item = TokenTree>, key: &Ident) -> Result<ColumnKind> {
+    let expected = || Error::new(key.span(), format!("expected `{key}(group_by = \"...\", child = Type)`"));
+    let Some(TokenTree::Group(group)) = tokens.next() else {
+        return Err(expected());
+    };
+    let (mut group_by, mut child) = (None, None);
+    for item in items(group.stream()) {
+        let mut tokens = item.into_iter();
+        match tokens.next() {
+            Some(TokenTree::Ident(key)) if key == "group_by" => {
+                let lit = value(&mut tokens, &key)?;
+                lit_str(&lit)?;
+                group_by = Some(lit);
+            }
+            Some(TokenTree::Ident(key)) if key == "child" => {
+                if !matches!(tokens.next(), Some(TokenTree::Punct(p)) if p.as_char() == '=') {
+                    return Err(expected());
+                }
+                child = Some(Box::new(parse2(tokens.collect())?));
+            }
+            None => {}
+            Some(_) => return Err(expected()),
+        }
+    }
+    match (group_by, child) {
+        (Some(group_by), Some(child)) => Ok(ColumnKind::HasMany { group_by, child }),
+        _ => Err(expected()),
+    }
+}
+
+/// Parses the `(Type, ..)` part of `try_types`.
+fn try_types(tokens: &mut impl Iterator<Item = TokenTree>, key: &Ident) -> Result<ColumnKind> {
+    let expected = || Error::new(key.span(), format!("expected `{key}(Type, ..)`"));
+    let Some(TokenTree::Group(group)) = tokens.next() else {
+        return Err(expected());
+    };
+    let types = punctuated::Punctuated::<Type, Token![,]>::parse_terminated
+        .parse2(group.stream())
+        .map_err(|_| expected())?;
+    if types.is_empty() {
+        return Err(expected());
+    }
+    Ok(ColumnKind::TryTypes(types.into_iter().collect()))
+}
+
+/// Parses the `= expr` part of a `key = expr` pair, such as a function path or a closure.
+fn expr_value(tokens: &mut impl Iterator<Item = TokenTree>, key: &Ident) -> Result<Expr> {
+    if matches!(tokens.next(), Some(TokenTree::Punct(p)) if p.as_char() == '=') {
+        if let Ok(expr) = parse2(tokens.collect()) {
+            return Ok(expr);
+        }
+    }
+    Err(Error::new(key.span(), format!("expected `{key} = function` or `{key} = |value| ..`")))
+}
+
+/// Parses the `= f` part of `with`, where `= [f, g]` composes the functions into one applying them left to right.
+fn with_value(tokens: &mut impl Iterator<Item = TokenTree>, key: &Ident) -> Result<Expr> {
+    let expr = expr_value(tokens, key)?;
+    let Expr::Array(pipeline) = expr else {
+        return Ok(expr);
+    };
+    if pipeline.elems.is_empty() {
+        return Err(Error::new_spanned(pipeline, "`with = [..]` needs at least one function"));
+    }
+    let mut value: Expr = parse_quote!(__value);
+    for function in &pipeline.elems {
+        value = parse_quote_spanned!(function.span()=> (#function)(#value));
+    }
+    Ok(parse_quote!(|__value| #value))
+}
+
+/// Parses the `= 2` or `= RowIndex(2)` part of `ordinal`, which must fit in a `usize`.
+fn ordinal_value(tokens: &mut impl Iterator<Item = TokenTree>, key: &Ident) -> Result<usize> {
+    let expected = || Error::new(key.span(), "expected `ordinal = <integer>` or `ordinal = RowIndex(<integer>)`");
+    if !matches!(tokens.next(), Some(TokenTree::Punct(p)) if p.as_char() == '=') {
+        return Err(expected());
+    }
+    let expr: Expr = parse2(tokens.collect()).map_err(|_| expected())?;
+    let expr = match expr {
+        Expr::Call(call)
+            if matches!(&*call.func, Expr::Path(p) if p.path.segments.last().is_some_and(|seg| seg.ident == "RowIndex"))
+                && call.args.len() == 1 =>
+        {
+            call.args.into_iter().next().unwrap()
+        }
+        expr => expr,
+    };
+    match expr {
+        Expr::Lit(ExprLit { lit: Lit::Int(int), .. }) if matches!(int.suffix(), "" | "usize") => int
+            .base10_parse()
+            .map_err(|_| Error::new(int.span(), format!("`ordinal` must fit in a `usize`, {} doesn't", int.base10_digits()))),
+        Expr::Unary(ExprUnary { op: UnOp::Neg(_), .. }) => Err(Error::new_spanned(expr, "`ordinal` can't be negative")),
+        _ => Err(expected()),
+    }
+}
+
+/// Parses the `= path` part of a `key = path` pair.
+fn path_value(mut tokens: impl Iterator<Item = TokenTree>, key: &Ident) -> Result<Path> {
+    if matches!(tokens.next(), Some(TokenTree::Punct(p)) if p.as_char() == '=') {
+        if let Ok(path) = parse2(tokens.collect()) {
+            return Ok(path);
+        }
+    }
+    Err(Error::new(key.span(), format!("expected `{key} = path`")))
+}
+
+pub fn lit_str(lit: &Literal) -> Result<String> {
+    match Lit::new(lit.clone()) {
+        Lit::Str(s) => Ok(s.value()),
+        _ => Err(Error::new(lit.span(), "expected a string literal")),
+    }
+}
+
+/// Parses a `rename_fmt` pattern, whose only placeholders are `{index}` and `{name}`.
+fn rename_fmt(lit: &Literal) -> Result<String> {
+    let fmt = lit_str(lit)?;
+    let mut rest = fmt.as_str();
+    while let Some(start) = rest.find('{') {
+        let placeholder = rest[start..].find('}').map(|end| &rest[start..=start + end]);
+        match placeholder {
+            Some(p @ ("{index}" | "{name}")) => rest = &rest[start + p.len()..],
+            _ => {
+                return Err(Error::new(
+                    lit.span(),
+                    "unknown placeholder in `rename_fmt`, expected `{index}` or `{name}`",
+                ))
+            }
+        }
+    }
+    Ok(fmt)
+}
+
+fn rename_rule(lit: &Literal) -> Result<RenameRule> {
+    RenameRule::parse(&lit_str(lit)?).ok_or_else(|| {
+        Error::new(
+            lit.span(),
+            format!("unknown rename rule, expected one of {}", RenameRule::VARIANTS),
+        )
+    })
+}
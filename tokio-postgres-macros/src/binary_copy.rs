@@ -0,0 +1,132 @@
+use crate::{
+    attr::{ColumnAttr, ColumnKind},
+    skip_value, try_get, try_types,
+};
+use quote2::{
+    proc_macro2::{Literal, TokenStream, TokenTree},
+    quote, Quote,
+};
+use syn::{ext::IdentExt, *};
+
+/// Generates `TryFrom<&BinaryCopyOutRow>`, reading the fields by position, as binary `COPY` rows have no column names.
+pub fn expand(input: &DeriveInput, fields: &Fields, attrs: &[ColumnAttr]) -> Result<TokenStream> {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = &input.generics.split_for_impl();
+
+    for (field, attr) in fields.iter().zip(attrs) {
+        if matches!(attr.kind, ColumnKind::Flatten | ColumnKind::FlattenRest | ColumnKind::HasMany { .. }) {
+            return Err(Error::new_spanned(
+                field,
+                "`flatten`, `flatten_rest` and `has_many` can't be combined with `binary_copy`",
+            ));
+        }
+    }
+
+    let values = quote(|tokens| {
+        let mut idx = 0usize;
+        for (field, attr) in fields.iter().zip(attrs) {
+            if let Some(name) = &field.ident {
+                quote!(tokens, { #name: });
+            }
+            match attr.kind {
+                ColumnKind::Skip => {
+                    skip_value(tokens, &field.ty);
+                    continue;
+                }
+                ColumnKind::InfallibleGet => {
+                    quote!(tokens, {
+                        r.get(#idx),
+                    });
+                }
+                ColumnKind::ByteaBase64 => {
+                    let ty = &field.ty;
+                    quote!(tokens, {
+                        <#ty as ::tokio_postgres_utils::__private::Base64Field>::from_column(r.try_get(#idx)?),
+                    });
+                }
+                ColumnKind::Decimal => {
+                    let ty = &field.ty;
+                    quote!(tokens, {
+                        <#ty as ::tokio_postgres_utils::__private::DecimalField>::from_column(r.try_get(#idx)?),
+                    });
+                }
+                ColumnKind::Inet => {
+                    let ty = &field.ty;
+                    quote!(tokens, {
+                        <#ty as ::tokio_postgres_utils::__private::InetField>::from_column(r.try_get(#idx)?),
+                    });
+                }
+                ColumnKind::Uuid => {
+                    let ty = &field.ty;
+                    quote!(tokens, {
+                        <#ty as ::tokio_postgres_utils::__private::UuidField>::from_column(r.try_get(#idx)?),
+                    });
+                }
+                ColumnKind::FromStr => {
+                    let ty = &field.ty;
+                    quote!(tokens, {
+                        <#ty as ::std::str::FromStr>::from_str(r.try_get::<&str>(#idx)?)?,
+                    });
+                }
+                ColumnKind::Json => {
+                    let ty = &field.ty;
+                    let field = Literal::string(&field.ident.as_ref().map_or(idx.to_string(), |name| name.unraw().to_string()));
+                    quote!(tokens, {
+                        <#ty as ::tokio_postgres_utils::__private::JsonField>::from_json(#field, r.try_get(#idx)?)?,
+                    });
+                }
+                ColumnKind::With(ref with) => {
+                    let value = try_get(attr, &TokenStream::from(TokenTree::Literal(Literal::usize_suffixed(idx))));
+                    quote!(tokens, {
+                        (#with)(#value?),
+                    });
+                }
+                ColumnKind::TryTypes(ref types) => {
+                    let value = try_types(&field.ty, types, &TokenStream::from(TokenTree::Literal(Literal::usize_suffixed(idx))));
+                    quote!(tokens, { #value?, });
+                }
+                ColumnKind::ArrayOfComposite => {
+                    quote!(tokens, {
+                        r.try_get::<::std::vec::Vec<::tokio_postgres_utils::Composite<_>>>(#idx)?
+                            .into_iter()
+                            .map(|c| c.0)
+                            .collect(),
+                    });
+                }
+                _ => {
+                    let value = try_get(attr, &TokenStream::from(TokenTree::Literal(Literal::usize_suffixed(idx))));
+                    quote!(tokens, {
+                        #value?,
+                    });
+                }
+            }
+            idx += 1;
+        }
+    });
+
+    let body = quote(|tokens| match fields {
+        Fields::Named(_) => {
+            quote!(tokens, {
+                { #values }
+            });
+        }
+        Fields::Unnamed(_) => {
+            quote!(tokens, {
+                (#values)
+            });
+        }
+        Fields::Unit => {}
+    });
+
+    let mut tokens = TokenStream::new();
+    quote!(tokens, {
+        impl #impl_generics ::std::convert::TryFrom<&tokio_postgres::binary_copy::BinaryCopyOutRow> for #name #ty_generics #where_clause {
+            #[inline]
+            fn try_from(r: &tokio_postgres::binary_copy::BinaryCopyOutRow) -> ::std::result::Result<Self, Self::Error> {
+                Ok(Self #body)
+            }
+            type Error = ::std::boxed::Box<dyn ::std::error::Error + ::std::marker::Send + ::std::marker::Sync>;
+        }
+    });
+    Ok(tokens)
+}
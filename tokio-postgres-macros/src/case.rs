@@ -0,0 +1,141 @@
+/// Case conversion applied to field and variant names by `rename_all` and `rename_case`.
+#[derive(Clone, Copy)]
+pub enum RenameRule {
+    Lower,
+    Upper,
+    Snake,
+    Camel,
+    Pascal,
+    ScreamingSnake,
+    Kebab,
+    ScreamingKebab,
+}
+
+impl RenameRule {
+    pub const VARIANTS: &'static str = "\"lowercase\", \"UPPERCASE\", \"snake_case\", \"camelCase\", \"PascalCase\", \"SCREAMING_SNAKE_CASE\", \"kebab-case\", \"SCREAMING-KEBAB-CASE\"";
+
+    pub fn parse(rule: &str) -> Option<Self> {
+        Some(match rule {
+            "lowercase" => RenameRule::Lower,
+            "UPPERCASE" => RenameRule::Upper,
+            "snake_case" => RenameRule::Snake,
+            "camelCase" => RenameRule::Camel,
+            "PascalCase" => RenameRule::Pascal,
+            "SCREAMING_SNAKE_CASE" => RenameRule::ScreamingSnake,
+            "kebab-case" => RenameRule::Kebab,
+            "SCREAMING-KEBAB-CASE" => RenameRule::ScreamingKebab,
+            _ => return None,
+        })
+    }
+
+    /// Converts a `snake_case` field name.
+    ///
+    /// Only the underscores between words separate them, leading and trailing ones are kept as is,
+    /// so `_id` doesn't collide with `id` and a name of underscores alone isn't emptied.
+    pub fn apply(self, field: &str) -> String {
+        let words = field.trim_matches('_');
+        let start = field.len() - field.trim_start_matches('_').len();
+        let (prefix, suffix) = (&field[..start], &field[start + words.len()..]);
+        let words = match self {
+            RenameRule::Lower => words.to_lowercase(),
+            RenameRule::Upper => words.to_uppercase(),
+            RenameRule::Snake => words.to_owned(),
+            RenameRule::ScreamingSnake => words.to_ascii_uppercase(),
+            RenameRule::Kebab => words.replace('_', "-"),
+            RenameRule::ScreamingKebab => words.to_ascii_uppercase().replace('_', "-"),
+            RenameRule::Pascal | RenameRule::Camel => {
+                let mut cased = String::new();
+                let mut capitalize = true;
+                for (i, ch) in words.char_indices() {
+                    if ch == '_' {
+                        capitalize = true;
+                    } else if i == 0 && matches!(self, RenameRule::Camel) {
+                        cased.push(ch.to_ascii_lowercase());
+                        capitalize = false;
+                    } else if capitalize {
+                        cased.push(ch.to_ascii_uppercase());
+                        capitalize = false;
+                    } else {
+                        cased.push(ch);
+                    }
+                }
+                cased
+            }
+        };
+        format!("{prefix}{words}{suffix}")
+    }
+
+    /// Converts a `PascalCase` variant name.
+    pub fn apply_to_variant(self, variant: &str) -> String {
+        match self {
+            RenameRule::Lower => return variant.to_lowercase(),
+            RenameRule::Upper => return variant.to_uppercase(),
+            _ => {}
+        }
+        let mut snake = String::new();
+        for (i, ch) in variant.char_indices() {
+            if i > 0 && ch.is_ascii_uppercase() {
+                snake.push('_');
+            }
+            snake.push(ch.to_ascii_lowercase());
+        }
+        self.apply(&snake)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RenameRule;
+
+    const RULES: [&str; 8] = [
+        "lowercase",
+        "UPPERCASE",
+        "snake_case",
+        "camelCase",
+        "PascalCase",
+        "SCREAMING_SNAKE_CASE",
+        "kebab-case",
+        "SCREAMING-KEBAB-CASE",
+    ];
+
+    fn apply(field: &str) -> Vec<String> {
+        RULES.iter().map(|rule| RenameRule::parse(rule).unwrap().apply(field)).collect()
+    }
+
+    #[test]
+    fn converts_words() {
+        assert_eq!(
+            apply("user_id"),
+            ["user_id", "USER_ID", "user_id", "userId", "UserId", "USER_ID", "user-id", "USER-ID"]
+        );
+        assert_eq!(apply("x"), ["x", "X", "x", "x", "X", "X", "x", "X"]);
+    }
+
+    #[test]
+    fn keeps_leading_and_trailing_underscores() {
+        assert_eq!(apply("a_"), ["a_", "A_", "a_", "a_", "A_", "A_", "a_", "A_"]);
+        assert_eq!(apply("_b"), ["_b", "_B", "_b", "_b", "_B", "_B", "_b", "_B"]);
+        assert_eq!(apply("_c_d_"), ["_c_d_", "_C_D_", "_c_d_", "_cD_", "_CD_", "_C_D_", "_c-d_", "_C-D_"]);
+        assert_eq!(apply("__id"), ["__id", "__ID", "__id", "__id", "__Id", "__ID", "__id", "__ID"]);
+    }
+
+    #[test]
+    fn keeps_names_of_underscores_alone() {
+        assert_eq!(apply("__"), ["__"; 8]);
+        assert_eq!(apply("_"), ["_"; 8]);
+    }
+
+    #[test]
+    fn converts_variants() {
+        let rule = |rule| RenameRule::parse(rule).unwrap();
+        assert_eq!(rule("snake_case").apply_to_variant("InProgress"), "in_progress");
+        assert_eq!(rule("kebab-case").apply_to_variant("InProgress"), "in-progress");
+        assert_eq!(rule("camelCase").apply_to_variant("InProgress"), "inProgress");
+        assert_eq!(rule("lowercase").apply_to_variant("InProgress"), "inprogress");
+    }
+
+    #[test]
+    fn rejects_unknown_rules() {
+        assert!(RenameRule::parse("Title Case").is_none());
+    }
+}
@@ -0,0 +1,56 @@
+use crate::attr::{column_name, ColumnAttr, ColumnKind, ContainerAttr};
+use quote2::{
+    proc_macro2::{Literal, TokenStream},
+    quote, Quote,
+};
+use syn::*;
+
+/// Keeps how each field is read in a `DEBUG_MAPPING` constant of the struct, for the `debug-mapping` feature.
+pub fn expand(input: &DeriveInput, fields: &Fields, attrs: &[ColumnAttr], container: &ContainerAttr) -> TokenStream {
+    let name = &input.ident;
+    let out = mapping(name, fields, attrs, container);
+    let (impl_generics, ty_generics, where_clause) = &input.generics.split_for_impl();
+    let out = Literal::string(&out);
+    let mut tokens = TokenStream::new();
+    quote!(tokens, {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// How each field is read, as described by the `debug-mapping` feature.
+            pub const DEBUG_MAPPING: &'static str = #out;
+        }
+    });
+    tokens
+}
+
+/// Describes how each field is read.
+fn mapping(name: &Ident, fields: &Fields, attrs: &[ColumnAttr], container: &ContainerAttr) -> String {
+    let mut out = format!("column mapping of `{name}`:");
+    for (i, (field, attr)) in fields.iter().zip(attrs).enumerate() {
+        let Some(ident) = &field.ident else {
+            out += &format!("\n    {i} <- column #{i}");
+            continue;
+        };
+        let source = match attr.kind {
+            ColumnKind::Skip => "skipped".to_owned(),
+            ColumnKind::Flatten => "flattened".to_owned(),
+            ColumnKind::FlattenOwned => "the whole owned row".to_owned(),
+            ColumnKind::HasMany { .. } => "has many, grouped by `group_and_map`".to_owned(),
+            ColumnKind::FlattenRest => "all remaining columns".to_owned(),
+            ColumnKind::FromRow(_) => "the whole row, through `from_row`".to_owned(),
+            _ if attr.ordinal.is_some() => format!("column #{}", attr.ordinal.unwrap()),
+            _ => {
+                let column = column_name(ident, attr, container).unwrap().to_string();
+                if attr.name_fn.is_some() {
+                    format!("{column}, or the column `name_fn` names for the context")
+                } else if container.lenient {
+                    format!("{column} (case-insensitive, else column #{i})")
+                } else if container.case_insensitive {
+                    format!("{column} (case-insensitive)")
+                } else {
+                    column
+                }
+            }
+        };
+        out += &format!("\n    {ident} <- {source}");
+    }
+    out
+}
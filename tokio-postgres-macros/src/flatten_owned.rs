@@ -0,0 +1,102 @@
+use crate::{
+    attr::{ColumnAttr, ColumnKind, ContainerAttr},
+    base_default, construct, skip_value,
+};
+use quote2::{proc_macro2::TokenStream, quote, Quote};
+use syn::*;
+
+/// With a `flatten_owned` field, forwards an owned `Row` to it instead of implementing the by-reference impls.
+///
+/// A `Row` can only be moved once, so every other field must be skipped. With `fallible`,
+/// `TryFrom<Row>` is implemented with the error of the field's `TryFrom<Row>` impl, otherwise `From<Row>`.
+pub fn expand(
+    input: &DeriveInput,
+    fields: &Fields,
+    attrs: &[ColumnAttr],
+    container: &ContainerAttr,
+    fallible: bool,
+) -> Result<Option<TokenStream>> {
+    let Some(owned) = fields.iter().zip(attrs).position(|(_, attr)| matches!(attr.kind, ColumnKind::FlattenOwned)) else {
+        return Ok(None);
+    };
+    for (field, attr) in fields.iter().zip(attrs) {
+        if !matches!(attr.kind, ColumnKind::FlattenOwned | ColumnKind::Skip) {
+            return Err(Error::new_spanned(
+                field,
+                "`flatten_owned` moves the whole row into its field, so every other field must be `skip`",
+            ));
+        }
+    }
+    let conflicts = [
+        (container.composite, "composite"),
+        (container.from_column.is_some(), "from_column"),
+        (container.binary_copy, "binary_copy"),
+        (container.row_access, "row_access"),
+        (container.collect_errors, "collect_errors"),
+        (container.strict_flatten, "strict_flatten"),
+        (container.table.is_some(), "table"),
+    ];
+    if let Some((_, attr_name)) = conflicts.iter().find(|(on, _)| *on) {
+        return Err(Error::new_spanned(&input.ident, format!("`{attr_name}` can't be combined with `flatten_owned`")));
+    }
+    let owned_ty = &fields.iter().nth(owned).unwrap().ty;
+
+    let name = &input.ident;
+    let mut generics = input.generics.clone();
+    let bound: WherePredicate = if fallible {
+        parse_quote!(#owned_ty: ::std::convert::TryFrom<tokio_postgres::Row>)
+    } else {
+        parse_quote!(#owned_ty: ::std::convert::From<tokio_postgres::Row>)
+    };
+    generics.make_where_clause().predicates.push(bound);
+    let (impl_generics, ty_generics, where_clause) = &generics.split_for_impl();
+
+    let named = matches!(fields, Fields::Named(_));
+    let body = quote(|tokens| {
+        for (field, attr) in fields.iter().zip(attrs) {
+            let skip = matches!(attr.kind, ColumnKind::Skip);
+            if skip && named && (container.base_default || container.builder.is_some()) {
+                continue;
+            }
+            if let (Some(name), None) = (&field.ident, &container.builder) {
+                quote!(tokens, { #name: });
+            }
+            if skip {
+                skip_value(tokens, &field.ty);
+            } else if fallible {
+                quote!(tokens, { ::std::convert::TryFrom::try_from(r)?, });
+            } else {
+                quote!(tokens, { ::std::convert::From::from(r), });
+            }
+        }
+        if named {
+            base_default(tokens, container);
+        }
+    });
+    let mut literal = TokenStream::new();
+    construct(&mut literal, container, named, body);
+
+    let mut tokens = TokenStream::new();
+    if fallible {
+        quote!(tokens, {
+            impl #impl_generics ::std::convert::TryFrom<tokio_postgres::Row> for #name #ty_generics #where_clause {
+                type Error = <#owned_ty as ::std::convert::TryFrom<tokio_postgres::Row>>::Error;
+
+                #[inline]
+                fn try_from(r: tokio_postgres::Row) -> ::std::result::Result<Self, Self::Error> {
+                    ::std::result::Result::Ok(#literal)
+                }
+            }
+        });
+    } else {
+        quote!(tokens, {
+            impl #impl_generics ::std::convert::From<tokio_postgres::Row> for #name #ty_generics #where_clause {
+                #[inline]
+                fn from(r: tokio_postgres::Row) -> Self {
+                    #literal
+                }
+            }
+        });
+    }
+    Ok(Some(tokens))
+}
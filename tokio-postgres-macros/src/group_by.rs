@@ -0,0 +1,63 @@
+use crate::attr::{sql_column, ColumnAttr, ContainerAttr, Grouping};
+use quote2::{
+    proc_macro2::{Literal, TokenStream},
+    quote, Quote,
+};
+use syn::*;
+
+/// With `group_key` or `aggregate` fields, the `group_by_sql` and `select_expr_sql` functions of a reporting query.
+///
+/// Every field read by name must be one or the other, as a column that is neither can't be selected by a grouped query.
+pub fn expand(input: &DeriveInput, generics: &Generics, fields: &Fields, attrs: &[ColumnAttr], container: &ContainerAttr) -> Result<TokenStream> {
+    let mut tokens = TokenStream::new();
+    let Some((grouped, _)) = fields.iter().zip(attrs).find(|(_, attr)| attr.grouping.is_some()) else {
+        return Ok(tokens);
+    };
+    if !matches!(fields, Fields::Named(_)) {
+        return Err(Error::new_spanned(grouped, "`group_key` and `aggregate` require a struct with named fields"));
+    }
+    if let Some((_, attr_name)) = [(container.composite, "composite"), (container.from_column.is_some(), "from_column")]
+        .iter()
+        .find(|(on, _)| *on)
+    {
+        return Err(Error::new_spanned(grouped, format!("`group_key` and `aggregate` can't be combined with `{attr_name}`")));
+    }
+    let (mut keys, mut exprs) = (Vec::new(), Vec::new());
+    for (field, attr) in fields.iter().zip(attrs) {
+        let Some(column) = sql_column(field.ident.as_ref().unwrap(), attr, container) else {
+            continue;
+        };
+        match &attr.grouping {
+            Some(Grouping::Key) => {
+                exprs.push(column.clone());
+                keys.push(column);
+            }
+            Some(Grouping::Aggregate(function)) => exprs.push(format!("{function}({column}) AS {column}")),
+            None => {
+                return Err(Error::new_spanned(
+                    field,
+                    "every field of a struct with `group_key` or `aggregate` fields must be one of them, or `skip`",
+                ))
+            }
+        }
+    }
+    let group_by = Literal::string(&if keys.is_empty() { String::new() } else { format!("GROUP BY {}", keys.join(", ")) });
+    let exprs = Literal::string(&exprs.join(", "));
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = &generics.split_for_impl();
+    quote!(tokens, {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Builds the `GROUP BY` clause of the `#[column(group_key)]` columns, or an empty string without any.
+            pub fn group_by_sql() -> ::std::string::String {
+                ::std::string::ToString::to_string(#group_by)
+            }
+
+            /// Lists the `group_key` columns and the `aggregate` expressions, each named after its column.
+            pub fn select_expr_sql() -> ::std::string::String {
+                ::std::string::ToString::to_string(#exprs)
+            }
+        }
+    });
+    Ok(tokens)
+}
@@ -0,0 +1,93 @@
+use crate::attr::{column_name, lit_str, ColumnAttr, ColumnKind, ContainerAttr};
+use quote2::{
+    proc_macro2::{Literal, TokenStream},
+    quote, Quote,
+};
+use syn::{ext::IdentExt, *};
+
+/// With a `has_many` field, a `group_and_map` function folding the rows of a 1:N join into parents.
+///
+/// Each row is read as a parent holding at most one child, then merged into the earlier parent
+/// with the same `group_by` field, if any. With `fallible`, rows are read through `TryFrom` instead of `From`.
+pub fn group_and_map(
+    input: &DeriveInput,
+    generics: &Generics,
+    fields: &Fields,
+    attrs: &[ColumnAttr],
+    container: &ContainerAttr,
+    fallible: bool,
+) -> Result<TokenStream> {
+    let mut tokens = TokenStream::new();
+    let mut has_many = fields
+        .iter()
+        .zip(attrs)
+        .filter_map(|(field, attr)| match &attr.kind {
+            ColumnKind::HasMany { group_by, .. } => Some((field, group_by)),
+            _ => None,
+        });
+    let Some((field, group_by)) = has_many.next() else {
+        return Ok(tokens);
+    };
+    if let Some((other, _)) = has_many.next() {
+        return Err(Error::new_spanned(other, "only one `has_many` field is supported per struct"));
+    }
+    let Some(children) = &field.ident else {
+        return Err(Error::new_spanned(field, "`has_many` requires a struct with named fields"));
+    };
+    let column = lit_str(group_by)?;
+    let key = fields.iter().zip(attrs).find_map(|(field, attr)| {
+        let name = field.ident.as_ref()?;
+        let read = column_name(name, attr, container)?;
+        (lit_str(&read).ok()? == column).then_some(name)
+    });
+    let Some(key) = key else {
+        return Err(Error::new_spanned(
+            group_by,
+            format!("`group_by` must name a column read by a field of this struct, found none reading `{column}`"),
+        ));
+    };
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = &generics.split_for_impl();
+    let (ret, read, done) = if fallible {
+        let mut ret = TokenStream::new();
+        quote!(ret, { ::std::result::Result<::std::vec::Vec<Self>, ::tokio_postgres_utils::BoxError> });
+        let mut read = TokenStream::new();
+        quote!(read, { ::std::convert::TryFrom::try_from(r).map_err(::std::convert::Into::<::tokio_postgres_utils::BoxError>::into)? });
+        let mut done = TokenStream::new();
+        quote!(done, { ::std::result::Result::Ok(parents) });
+        (ret, read, done)
+    } else {
+        let mut ret = TokenStream::new();
+        quote!(ret, { ::std::vec::Vec<Self> });
+        let mut read = TokenStream::new();
+        quote!(read, { ::std::convert::From::from(r) });
+        let mut done = TokenStream::new();
+        quote!(done, { parents });
+        (ret, read, done)
+    };
+    let doc = Literal::string(&format!(
+        " Maps the rows of a join into parents, grouping the `{}` of the rows sharing `{}`.",
+        children.unraw(),
+        key.unraw()
+    ));
+    quote!(tokens, {
+        impl #impl_generics #name #ty_generics #where_clause {
+            #[doc = #doc]
+            ///
+            /// Parents keep the order of their first row, rows without a child, as from an unmatched `LEFT JOIN`, add none.
+            pub fn group_and_map(rows: &[tokio_postgres::Row]) -> #ret {
+                let mut parents: ::std::vec::Vec<Self> = ::std::vec::Vec::new();
+                for r in rows {
+                    let parent: Self = #read;
+                    match parents.iter_mut().rev().find(|p| p.#key == parent.#key) {
+                        ::std::option::Option::Some(p) => ::std::iter::Extend::extend(&mut p.#children, parent.#children),
+                        ::std::option::Option::None => parents.push(parent),
+                    }
+                }
+                #done
+            }
+        }
+    });
+    Ok(tokens)
+}
@@ -0,0 +1,314 @@
+use crate::{
+    attr::{column_name, sql_column, ColumnAttr, ColumnKind, ContainerAttr, WriteRole},
+    column_attrs, struct_fields,
+};
+use quote2::{
+    proc_macro2::{Literal, Span, TokenStream},
+    quote, Quote,
+};
+use syn::*;
+
+pub fn expand(input: &DeriveInput) -> Result<TokenStream> {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = &input.generics.split_for_impl();
+    let all_fields = struct_fields(input, "Insert")?;
+    let Fields::Named(fields) = all_fields else {
+        return Err(Error::new_spanned(
+            name,
+            "`Insert` can only be derived for structs with named fields",
+        ));
+    };
+    let container = ContainerAttr::new(&input.attrs, all_fields)?;
+    let attrs = column_attrs(all_fields)?;
+
+    for (field, attr) in fields.named.iter().zip(&attrs) {
+        // A field that isn't inserted may be read in any way.
+        if let Some(WriteRole::SkipInsert) = attr.write {
+            continue;
+        }
+        if attr.ordinal.is_some() {
+            return Err(Error::new_spanned(field, "`ordinal` is not supported by `Insert`"));
+        }
+        let unsupported = match attr.kind {
+            ColumnKind::Flatten => "flatten",
+            ColumnKind::FlattenOwned => "flatten_owned",
+            ColumnKind::HasMany { .. } => "has_many",
+            ColumnKind::FlattenRest => "flatten_rest",
+            ColumnKind::ArrayOfComposite => "array_of_composite",
+            ColumnKind::FromStr => "from_str",
+            ColumnKind::Json => "json",
+            ColumnKind::FromRow(_) => "from_row",
+            ColumnKind::TryTypes(_) => "try_types",
+            _ => continue,
+        };
+        return Err(Error::new_spanned(
+            field,
+            format!("`{unsupported}` is not supported by `Insert`"),
+        ));
+    }
+
+    // Columns and params come from the same fields, in the same order, so `$n` always binds the `n`th param.
+    let inserted = || {
+        fields
+            .named
+            .iter()
+            .zip(&attrs)
+            .filter(|(_, attr)| !matches!(attr.kind, ColumnKind::Skip) && !matches!(attr.write, Some(WriteRole::SkipInsert)))
+    };
+
+    let columns = quote(|tokens| {
+        for (field, attr) in inserted() {
+            let column = column_name(field.ident.as_ref().unwrap(), attr, &container);
+            quote!(tokens, { #column, });
+        }
+    });
+
+    let types = quote(|tokens| {
+        for (field, attr) in inserted() {
+            // The written type of a `write_with` field is only known to the compiler.
+            match param_type(&field.ty, &attr.kind).filter(|_| attr.write_with.is_none()) {
+                Some(ty) => {
+                    let ty = Ident::new(&ty, Span::call_site());
+                    quote!(tokens, { ::std::option::Option::Some(tokio_postgres::types::Type::#ty), });
+                }
+                None => {
+                    quote!(tokens, { ::std::option::Option::None, });
+                }
+            }
+        }
+    });
+
+    // A `bytea_base64` field is bound as its decoded bytes, through a `WriteWith` impl like `write_with` fields.
+    let written = |attr: &ColumnAttr| attr.write_with.is_some() || matches!(attr.kind, ColumnKind::ByteaBase64);
+
+    let params = quote(|tokens| {
+        for (field, attr) in inserted() {
+            let ident = &field.ident;
+            if written(attr) {
+                let index = attr.index;
+                quote!(tokens, { ::tokio_postgres_utils::__private::written::<Self, #index>(&self.#ident), });
+            } else {
+                quote!(tokens, { &self.#ident, });
+            }
+        }
+    });
+
+    let owned_params = quote(|tokens| {
+        for (field, attr) in inserted() {
+            let ident = &field.ident;
+            if written(attr) {
+                let index = attr.index;
+                quote!(tokens, {
+                    ::std::boxed::Box::new(::tokio_postgres_utils::__private::into_written::<Self, #index>(self.#ident))
+                        as ::std::boxed::Box<dyn tokio_postgres::types::ToSql + ::std::marker::Send + ::std::marker::Sync>,
+                });
+            } else {
+                quote!(tokens, {
+                    ::std::boxed::Box::new(self.#ident) as ::std::boxed::Box<dyn tokio_postgres::types::ToSql + ::std::marker::Send + ::std::marker::Sync>,
+                });
+            }
+        }
+    });
+
+    // Each `write_with` and `bytea_base64` field is bound through a `WriteWith` impl on the struct, indexed by the field position.
+    let write_with = quote(|tokens| {
+        for (field, attr) in inserted() {
+            let (ty, index) = (&field.ty, attr.index);
+            let mut value = TokenStream::new();
+            if let Some(write) = &attr.write_with {
+                quote!(value, { &(#write)(value) });
+            } else if let ColumnKind::ByteaBase64 = attr.kind {
+                quote!(value, { &<#ty as ::tokio_postgres_utils::__private::Base64Field>::to_column(value)? });
+            } else {
+                continue;
+            }
+            quote!(tokens, {
+                impl #impl_generics ::tokio_postgres_utils::__private::WriteWith<#index> for #name #ty_generics #where_clause {
+                    type Field = #ty;
+
+                    #[inline]
+                    fn to_sql_checked(
+                        value: &#ty,
+                        ty: &tokio_postgres::types::Type,
+                        out: &mut tokio_postgres::types::private::BytesMut,
+                    ) -> ::std::result::Result<tokio_postgres::types::IsNull, ::tokio_postgres_utils::BoxError> {
+                        tokio_postgres::types::ToSql::to_sql_checked(#value, ty, out)
+                    }
+                }
+            });
+        }
+    });
+
+    // Boxed params must be `Send + 'static`, so a struct borrowing e.g. `&'a str` only has `into_params` for `'a = 'static`.
+    let mut owned = input.generics.clone();
+    for (field, _) in inserted() {
+        let ty = &field.ty;
+        owned
+            .make_where_clause()
+            .predicates
+            .push(parse_quote!(#ty: tokio_postgres::types::ToSql + ::std::marker::Send + ::std::marker::Sync + 'static));
+    }
+    let (_, _, owned_where_clause) = &owned.split_for_impl();
+
+    let column_list = quote(|tokens| {
+        if !container.quote_identifiers {
+            return;
+        }
+        let list = inserted()
+            .filter_map(|(field, attr)| sql_column(field.ident.as_ref().unwrap(), attr, &container))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let list = Literal::string(&list);
+        quote!(tokens, {
+            #[inline]
+            fn column_list() -> ::std::string::String {
+                ::std::string::ToString::to_string(#list)
+            }
+        });
+    });
+
+    let mut tokens = TokenStream::new();
+    quote!(tokens, {
+        impl #impl_generics ::tokio_postgres_utils::Insert for #name #ty_generics #where_clause {
+            const COLUMNS: &'static [&'static str] = &[#columns];
+            const PARAM_TYPES: &'static [::std::option::Option<tokio_postgres::types::Type>] = &[#types];
+
+            #[inline]
+            fn as_params(&self) -> ::std::vec::Vec<&(dyn tokio_postgres::types::ToSql + ::std::marker::Sync)> {
+                ::std::vec![#params]
+            }
+
+            #column_list
+        }
+
+        #write_with
+    });
+    quote!(tokens, {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Moves the field values into owned params, one per column, e.g. to bind them in a spawned task.
+            pub fn into_params(self) -> ::std::vec::Vec<::std::boxed::Box<dyn tokio_postgres::types::ToSql + ::std::marker::Send + ::std::marker::Sync>>
+            #owned_where_clause
+            {
+                ::std::vec![#owned_params]
+            }
+        }
+    });
+    if let Some(table) = &container.table {
+        quote!(tokens, {
+            impl #impl_generics #name #ty_generics #where_clause {
+                /// Builds an `INSERT` statement into the `#[column(table)]` table.
+                pub fn insert_into_sql() -> ::std::string::String {
+                    <Self as ::tokio_postgres_utils::Insert>::insert_sql(#table)
+                }
+            }
+        });
+    }
+
+    // The conflict clause only names columns, so `upsert_sql` binds the same params as `insert_sql`.
+    let keys = inserted()
+        .filter(|(_, attr)| matches!(attr.write, Some(WriteRole::Key)))
+        .filter_map(|(field, attr)| sql_column(field.ident.as_ref().unwrap(), attr, &container))
+        .collect::<Vec<_>>();
+    if !keys.is_empty() {
+        let sets = inserted()
+            .filter(|(_, attr)| !matches!(attr.write, Some(WriteRole::Key)))
+            .filter_map(|(field, attr)| sql_column(field.ident.as_ref().unwrap(), attr, &container))
+            .map(|column| format!("{column} = EXCLUDED.{column}"))
+            .collect::<Vec<_>>();
+        let action = if sets.is_empty() {
+            "DO NOTHING".to_owned()
+        } else {
+            format!("DO UPDATE SET {}", sets.join(", "))
+        };
+        let conflict = Literal::string(&format!(" ON CONFLICT ({}) {action}", keys.join(", ")));
+        quote!(tokens, {
+            impl #impl_generics #name #ty_generics #where_clause {
+                /// Builds an `INSERT` statement updating the row whose `#[column(key)]` columns conflict, with the params of `as_params`.
+                pub fn upsert_sql(table: &str) -> ::std::string::String {
+                    <Self as ::tokio_postgres_utils::Insert>::insert_sql(table) + #conflict
+                }
+            }
+        });
+        if let Some(table) = &container.table {
+            quote!(tokens, {
+                impl #impl_generics #name #ty_generics #where_clause {
+                    /// Builds an upsert into the `#[column(table)]` table.
+                    pub fn upsert_into_sql() -> ::std::string::String {
+                        Self::upsert_sql(#table)
+                    }
+                }
+            });
+        }
+    }
+    Ok(tokens)
+}
+
+/// The name of the `Type` constant a param of type `ty` is sent as, if known.
+///
+/// Only looks at the syntax, so aliases and types from other crates have no mapping.
+fn param_type(ty: &Type, kind: &ColumnKind) -> Option<String> {
+    match kind {
+        ColumnKind::Decimal => return Some("NUMERIC".into()),
+        ColumnKind::Inet => return Some("INET".into()),
+        ColumnKind::Uuid => return Some("UUID".into()),
+        ColumnKind::ByteaBase64 => return Some("BYTEA".into()),
+        _ => {}
+    }
+    let (array, elem) = match ty {
+        Type::Reference(r) => return param_type(&r.elem, kind),
+        Type::Paren(p) => return param_type(&p.elem, kind),
+        Type::Slice(s) => (true, &*s.elem),
+        Type::Path(p) => {
+            let segment = p.path.segments.last()?;
+            let arg = match &segment.arguments {
+                PathArguments::AngleBracketed(args) => match args.args.first() {
+                    Some(GenericArgument::Type(ty)) => Some(ty),
+                    _ => None,
+                },
+                _ => None,
+            };
+            match (segment.ident.to_string().as_str(), arg) {
+                ("Option" | "Box", Some(inner)) => return param_type(inner, kind),
+                ("Vec", Some(inner)) => (true, inner),
+                (_, Some(_)) => return None,
+                _ => (false, ty),
+            }
+        }
+        _ => return None,
+    };
+    let scalar = scalar_type(elem)?;
+    Some(match (array, scalar) {
+        (true, "u8") => "BYTEA".into(),
+        (_, "u8") => return None,
+        (true, scalar) => format!("{scalar}_ARRAY"),
+        (false, scalar) => scalar.into(),
+    })
+}
+
+/// The `Type` constant of a scalar, or `u8` whose only mapping is `BYTEA` for its slices.
+fn scalar_type(ty: &Type) -> Option<&'static str> {
+    let ty = match ty {
+        Type::Reference(r) => &*r.elem,
+        ty => ty,
+    };
+    let Type::Path(p) = ty else {
+        return None;
+    };
+    let segment = p.path.segments.last()?;
+    if !segment.arguments.is_empty() {
+        return None;
+    }
+    Some(match segment.ident.to_string().as_str() {
+        "bool" => "BOOL",
+        "u8" => "u8",
+        "i8" => "CHAR",
+        "i16" => "INT2",
+        "i32" => "INT4",
+        "i64" => "INT8",
+        "u32" => "OID",
+        "f32" => "FLOAT4",
+        "f64" => "FLOAT8",
+        "String" | "str" => "TEXT",
+        _ => return None,
+    })
+}
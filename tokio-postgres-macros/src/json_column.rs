@@ -0,0 +1,78 @@
+use crate::attr::ContainerAttr;
+use quote2::{proc_macro2::TokenStream, quote, Quote};
+use syn::*;
+
+/// With `from_json_column`, reads the whole struct from one `json` or `jsonb` column with `serde_json`,
+/// such as `to_jsonb(t.*) AS data`, instead of reading its fields from the row's columns.
+///
+/// With `fallible`, `TryFrom<&Row>` is implemented, otherwise `From<&Row>`, which panics if the column can't be read.
+pub fn expand(input: &DeriveInput, container: &ContainerAttr, fallible: bool) -> Result<Option<TokenStream>> {
+    let Some(column) = &container.from_json_column else {
+        return Ok(None);
+    };
+    let conflicts = [
+        (container.composite, "composite"),
+        (container.from_column.is_some(), "from_column"),
+        (container.binary_copy, "binary_copy"),
+        (container.row_access, "row_access"),
+        (container.collect_errors, "collect_errors"),
+        (container.strict_flatten, "strict_flatten"),
+        (container.base_default, "base_default"),
+        (container.builder.is_some(), "builder"),
+        (container.table.is_some(), "table"),
+    ];
+    if let Some((_, attr_name)) = conflicts.iter().find(|(on, _)| *on) {
+        return Err(Error::new_spanned(&input.ident, format!("`{attr_name}` can't be combined with `from_json_column`")));
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = &input.generics.split_for_impl();
+    let mut tokens = TokenStream::new();
+    if fallible {
+        quote!(tokens, {
+            impl #impl_generics ::std::convert::TryFrom<&tokio_postgres::Row> for #name #ty_generics #where_clause {
+                type Error = ::tokio_postgres_utils::BoxError;
+
+                #[inline]
+                fn try_from(r: &tokio_postgres::Row) -> ::std::result::Result<Self, Self::Error> {
+                    <Self as ::tokio_postgres_utils::__private::JsonField>::from_json(#column, r.try_get(#column)?)
+                }
+            }
+
+            impl #impl_generics ::tokio_postgres_utils::FromRow for #name #ty_generics #where_clause {
+                #[inline]
+                fn from_row(r: &tokio_postgres::Row) -> ::std::result::Result<Self, ::tokio_postgres_utils::BoxError> {
+                    ::std::convert::TryFrom::try_from(r)
+                }
+
+                #[inline]
+                fn columns() -> &'static [&'static str] {
+                    &[#column]
+                }
+            }
+        });
+    } else {
+        quote!(tokens, {
+            impl #impl_generics ::std::convert::From<&tokio_postgres::Row> for #name #ty_generics #where_clause {
+                #[inline]
+                fn from(r: &tokio_postgres::Row) -> Self {
+                    <Self as ::tokio_postgres_utils::__private::JsonField>::from_json(#column, r.get(#column))
+                        .unwrap_or_else(|err| ::std::panic!("{}", err))
+                }
+            }
+
+            impl #impl_generics ::tokio_postgres_utils::FromRow for #name #ty_generics #where_clause {
+                #[inline]
+                fn from_row(r: &tokio_postgres::Row) -> ::std::result::Result<Self, ::tokio_postgres_utils::BoxError> {
+                    <Self as ::tokio_postgres_utils::__private::JsonField>::from_json(#column, r.try_get(#column)?)
+                }
+
+                #[inline]
+                fn columns() -> &'static [&'static str] {
+                    &[#column]
+                }
+            }
+        });
+    }
+    Ok(Some(tokens))
+}
@@ -0,0 +1,54 @@
+use crate::{
+    attr::{ColumnAttr, ContainerAttr, WriteRole},
+    column_index,
+};
+use quote2::{
+    proc_macro2::{Literal, TokenStream},
+    quote, Quote,
+};
+use syn::{ext::IdentExt, *};
+
+/// With `key` fields, a `try_from_row_opt` function reading `None` from rows whose key columns are `NULL`.
+pub fn try_from_row_opt(input: &DeriveInput, generics: &Generics, fields: &Fields, attrs: &[ColumnAttr], container: &ContainerAttr) -> TokenStream {
+    let mut tokens = TokenStream::new();
+    if container.from_column.is_some() {
+        return tokens;
+    }
+    let keys: Vec<_> = fields
+        .iter()
+        .zip(attrs)
+        .filter(|(_, attr)| matches!(attr.write, Some(WriteRole::Key)))
+        .filter_map(|(field, attr)| Some((field.ident.as_ref()?, attr)))
+        .collect();
+    if keys.is_empty() {
+        return tokens;
+    }
+    let checks = quote(|tokens| {
+        for (i, (name, attr)) in keys.iter().enumerate() {
+            if i > 0 {
+                quote!(tokens, { || });
+            }
+            let column = column_index(name, attr, container, attr.index);
+            quote!(tokens, { ::tokio_postgres_utils::__private::is_null(r, #column) });
+        }
+    });
+    let names = keys.iter().map(|(name, _)| format!("`{}`", name.unraw())).collect::<Vec<_>>().join(", ");
+    let doc = Literal::string(&format!(" Reads the row, or `None` if the key column of {names} is `NULL`, as for a parent missing from an outer join."));
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = &generics.split_for_impl();
+    quote!(tokens, {
+        impl #impl_generics #name #ty_generics #where_clause {
+            #[doc = #doc]
+            pub fn try_from_row_opt(
+                r: &tokio_postgres::Row,
+            ) -> ::std::result::Result<::std::option::Option<Self>, <Self as ::std::convert::TryFrom<&tokio_postgres::Row>>::Error> {
+                if #checks {
+                    return ::std::result::Result::Ok(::std::option::Option::None);
+                }
+                ::std::convert::TryFrom::try_from(r).map(::std::option::Option::Some)
+            }
+        }
+    });
+    tokens
+}
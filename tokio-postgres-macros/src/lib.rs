@@ -1,12 +1,37 @@
-use std::cell::Cell;
-use quote2::{proc_macro2::{TokenStream, TokenTree, Literal}, quote, Quote};
-use syn::*;
+mod attr;
+mod binary_copy;
+mod case;
+#[cfg(feature = "debug-mapping")]
+mod debug;
+mod flatten_owned;
+mod group_by;
+mod has_many;
+mod insert;
+mod json_column;
+mod key_opt;
+mod order_by;
+mod partial_update;
+mod query_macro;
+mod repr_enum;
+mod to_sql_enum;
+mod trace;
+mod ty;
+
+use attr::{column_attr, column_name, ColumnAttr, ColumnKind, ContainerAttr, Rename};
+use ty::{is_lazy, is_once_cell, is_option, is_text, is_unit};
+use quote2::{
+    proc_macro2::{Literal, TokenStream, TokenTree},
+    quote, IntoTokens, Quote,
+};
+use syn::{ext::IdentExt, spanned::Spanned, *};
 
 /// Implements `From<&Row>` trait for a struct, allowing direct conversion from a database row to the struct.
 ///
+/// On a `#[repr(int)]` enum with `#[column(repr_enum = "..")]`, implements `TryFrom<&Row>` instead, matching the column against the discriminants.
+///
 /// ## Example
 ///
-/// ```rust
+/// ```rust, ignore
 /// use tokio_postgres_utils::FromRow;
 ///
 /// #[derive(FromRow)]
@@ -18,7 +43,7 @@ use syn::*;
 ///
 /// Expand into:
 ///
-/// ```
+/// ```rust, ignore
 /// impl From<&Row> for User {
 ///     fn from(row: &Row) -> Self {
 ///         Self {
@@ -31,125 +56,561 @@ use syn::*;
 #[proc_macro_derive(FromRow, attributes(column))]
 pub fn from_row(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
-    let name = input.ident;
-    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    expand_from_row(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
 
-    let body = quote(|tokens| match input.data {
-        Data::Struct(data) => match &data.fields {
-            Fields::Named(fields) => {
-                let body = quote(|tokens| {
-                    for field in &fields.named {
-                        if let Some(name) = &field.ident {
-                            quote!(tokens, { #name: });
-                            match column_attr(&field.attrs) {
-                                ColumnAttr::Flatten => {
-                                    quote!(tokens, {
-                                        ::std::convert::TryFrom::try_from(r).unwrap(),
-                                    });
-                                }
-                                ColumnAttr::Rename(rename) => {
-                                    quote!(tokens, {
-                                        r.get(#rename),
-                                    });
+fn expand_from_row(input: &DeriveInput) -> Result<TokenStream> {
+    let name = &input.ident;
+    if let Data::Enum(data) = &input.data {
+        return repr_enum::expand(input, data);
+    }
+    let fields = struct_fields(input, "FromRow")?;
+    let container = ContainerAttr::new(&input.attrs, fields)?;
+    if container.strict_flatten {
+        return Err(Error::new_spanned(name, "`strict_flatten` is only supported by `TryFromRow`"));
+    }
+    if container.row_access {
+        return Err(Error::new_spanned(name, "`row_access` is only supported by `TryFromRow`"));
+    }
+    if let Some(ctx) = &container.ctx {
+        return Err(Error::new_spanned(ctx, "`ctx` is only supported by `TryFromRow`"));
+    }
+    let attrs = column_attrs(fields)?;
+    check_skipped(fields, &attrs)?;
+    check_nth(fields, &attrs, &container)?;
+    check_row_reads(fields, &attrs, &container)?;
+    if let Some(field) = fields.iter().zip(&attrs).find_map(|(field, attr)| attr.name_fn.as_ref().map(|_| field)) {
+        return Err(Error::new_spanned(field, "`name_fn` is only supported by `TryFromRow`"));
+    }
+    if let Some(field) = fields.iter().zip(&attrs).find_map(|(field, attr)| attr.retry.map(|_| field)) {
+        return Err(Error::new_spanned(field, "`retry` is only supported by `TryFromRow`"));
+    }
+    if let Some(mut tokens) = flatten_owned::expand(input, fields, &attrs, &container, false)? {
+        tokens.extend(field_count(input, &attrs));
+        return Ok(tokens);
+    }
+    if let Some(mut tokens) = json_column::expand(input, &container, false)? {
+        tokens.extend(field_count(input, &attrs));
+        return Ok(tokens);
+    }
+    let generics = flatten_bounds(&input.generics, fields, &attrs, |ty| parse_quote_spanned!(ty.span()=> #ty: ::tokio_postgres_utils::FromRow));
+    let generics = decode_bounds(&generics, fields, &attrs);
+    let (impl_generics, ty_generics, where_clause) = &generics.split_for_impl();
+    let all_skipped = all_skipped(name, &attrs)?;
+
+    let columns = quote(|tokens| {
+        if let Fields::Named(fields) = fields {
+            for (field, attr) in fields.named.iter().zip(&attrs) {
+                if let Some(column) = column_name(field.ident.as_ref().unwrap(), attr, &container) {
+                    quote!(tokens, { #column, });
+                }
+            }
+        } else if let Some(column) = transparent_column(input, &attrs) {
+            quote!(tokens, { #column, });
+        }
+    });
+
+    let skipped = quote(|tokens| {
+        if let Fields::Named(fields) = fields {
+            for (field, attr) in fields.named.iter().zip(&attrs) {
+                if let ColumnKind::Skip = attr.kind {
+                    let field = Literal::string(&field.ident.as_ref().unwrap().unraw().to_string());
+                    quote!(tokens, { #field, });
+                }
+            }
+        }
+    });
+
+    let bindings = quote(|tokens| {
+        if let Fields::Named(fields) = fields {
+            let columns = fields
+                .named
+                .iter()
+                .zip(&attrs)
+                .filter_map(|(field, attr)| column_name(field.ident.as_ref().unwrap(), attr, &container));
+            for (i, column) in columns.enumerate() {
+                let ordinal = i + 1;
+                quote!(tokens, { (#column, #ordinal), });
+            }
+        }
+    });
+
+    let body = quote(|tokens| match fields {
+        Fields::Named(fields) => {
+            let body = quote(|tokens| {
+                for (i, (field, attr)) in fields.named.iter().zip(&attrs).enumerate() {
+                    let name = field.ident.as_ref().unwrap();
+                    if (container.base_default || container.builder.is_some()) && matches!(attr.kind, ColumnKind::Skip) {
+                        continue;
+                    }
+                    if container.builder.is_none() {
+                        quote!(tokens, { #name: });
+                    }
+                    match attr.kind {
+                        ColumnKind::Flatten => {
+                            quote!(tokens, {
+                                ::tokio_postgres_utils::FromRow::from_row(r).unwrap(),
+                            });
+                        }
+                        ColumnKind::Skip => skip_value(tokens, &field.ty),
+                        ColumnKind::HasMany { ref child, .. } => {
+                            quote!(tokens, {
+                                {
+                                    let child: ::std::option::Option<#child> = ::tokio_postgres_utils::FromRow::from_row(r).unwrap();
+                                    child.into_iter().collect()
                                 },
-                                ColumnAttr::None => {
-                                    let raw_str = name.to_string();
-                                    quote!(tokens, {
-                                        r.get(#raw_str),
-                                    });
-                                }
-                                ColumnAttr::Skip => {
-                                    quote!(tokens, {
-                                        ::std::default::Default::default(),
-                                    });
-                                }
-                            }
+                            });
+                        }
+                        ColumnKind::FlattenRest => {
+                            let consumed = consumed_columns(fields, &attrs, &container);
+                            quote!(tokens, {
+                                r.columns()
+                                    .iter()
+                                    .enumerate()
+                                    .filter(|(_, c)| !#consumed.contains(&c.name()))
+                                    .map(|(i, c)| (c.name().to_owned(), r.get(i)))
+                                    .collect(),
+                            });
+                        }
+                        ColumnKind::ByteaBase64 => {
+                            let column = column_index(name, attr, &container, i);
+                            let ty = &field.ty;
+                            quote!(tokens, {
+                                <#ty as ::tokio_postgres_utils::__private::Base64Field>::from_column(r.get(#column)),
+                            });
+                        }
+                        ColumnKind::Decimal => {
+                            let column = column_index(name, attr, &container, i);
+                            let ty = &field.ty;
+                            quote!(tokens, {
+                                <#ty as ::tokio_postgres_utils::__private::DecimalField>::from_column(r.get(#column)),
+                            });
+                        }
+                        ColumnKind::Inet => {
+                            let column = column_index(name, attr, &container, i);
+                            let ty = &field.ty;
+                            quote!(tokens, {
+                                <#ty as ::tokio_postgres_utils::__private::InetField>::from_column(r.get(#column)),
+                            });
+                        }
+                        ColumnKind::Uuid => {
+                            let column = column_index(name, attr, &container, i);
+                            let ty = &field.ty;
+                            quote!(tokens, {
+                                <#ty as ::tokio_postgres_utils::__private::UuidField>::from_column(r.get(#column)),
+                            });
+                        }
+                        ColumnKind::FromStr => {
+                            let column = &column_index(name, attr, &container, i);
+                            let ty = &field.ty;
+                            quote!(tokens, {
+                                {
+                                    let text: &str = r.get(#column);
+                                    <#ty as ::std::str::FromStr>::from_str(text)
+                                        .unwrap_or_else(|err| ::std::panic!("error parsing column {}: {}", #column, err))
+                                },
+                            });
+                        }
+                        ColumnKind::Json => {
+                            let column = column_index(name, attr, &container, i);
+                            let ty = &field.ty;
+                            let field = Literal::string(&name.unraw().to_string());
+                            quote!(tokens, {
+                                <#ty as ::tokio_postgres_utils::__private::JsonField>::from_json(#field, r.get(#column))
+                                    .unwrap_or_else(|err| ::std::panic!("{}", err)),
+                            });
+                        }
+                        ColumnKind::TryTypes(ref types) => {
+                            let value = try_types(&field.ty, types, &column_index(name, attr, &container, i));
+                            quote!(tokens, {
+                                #value.unwrap_or_else(|err| ::std::panic!("{}", err)),
+                            });
+                        }
+                        ColumnKind::With(ref with) => {
+                            let column = column_index(name, attr, &container, i);
+                            quote!(tokens, {
+                                (#with)(r.get(#column)),
+                            });
+                        }
+                        ColumnKind::FromRow(ref from_row) => {
+                            quote!(tokens, {
+                                (#from_row)(r),
+                            });
+                        }
+                        ColumnKind::ArrayOfComposite => {
+                            let column = column_index(name, attr, &container, i);
+                            quote!(tokens, {
+                                r.get::<_, ::std::vec::Vec<::tokio_postgres_utils::Composite<_>>>(#column)
+                                    .into_iter()
+                                    .map(|c| c.0)
+                                    .collect(),
+                            });
+                        }
+                        _ => {
+                            let column = column_index(name, attr, &container, i);
+                            quote!(tokens, {
+                                r.get(#column),
+                            });
                         }
                     }
-                });
-                quote!(tokens, {
-                    { #body }
-                });
-            }
-            Fields::Unnamed(fields) => {
-                let body = quote(|tokens| {
-                    for (i, _) in fields.unnamed.iter().enumerate() {
-                        let idx = Index::from(i);
-                        quote!(tokens, {
-                            r.get(#idx),
-                        });
-                    }
-                });
-                quote!(tokens, {
-                    (#body)
-                });
-            }
-            Fields::Unit => {}
-        },
-        Data::Enum(_) | Data::Union(_) => unimplemented!(),
+                }
+                base_default(tokens, &container);
+            });
+            construct(tokens, &container, true, body);
+        }
+        Fields::Unnamed(fields) => {
+            let body = quote(|tokens| {
+                if let Some(column) = transparent_column(input, &attrs) {
+                    quote!(tokens, { r.get(#column) });
+                    return;
+                }
+                for (i, _) in fields.unnamed.iter().enumerate() {
+                    let idx = Index::from(i);
+                    quote!(tokens, {
+                        r.get(#idx),
+                    });
+                }
+            });
+            construct(tokens, &container, false, body);
+        }
+        Fields::Unit => {
+            quote!(tokens, { Self });
+        }
     });
 
+    let mut body_tokens = TokenStream::new();
+    quote!(body_tokens, { { #body } });
+    let body = trace::wrap(name, &container, None, body_tokens);
+
+    let accepts_column = accepts_column(fields, &attrs, &container);
     let mut tokens = TokenStream::new();
     quote!(tokens, {
         impl #impl_generics ::std::convert::From<&tokio_postgres::Row> for #name #ty_generics #where_clause {
             #[inline]
             fn from(r: &tokio_postgres::Row) -> Self {
-                Self #body
+                #body
+            }
+        }
+
+        impl #impl_generics ::tokio_postgres_utils::FromRow for #name #ty_generics #where_clause {
+            #[inline]
+            fn from_row(r: &tokio_postgres::Row) -> ::std::result::Result<Self, ::tokio_postgres_utils::BoxError> {
+                ::std::result::Result::Ok(::std::convert::From::from(r))
+            }
+
+            #[inline]
+            fn columns() -> &'static [&'static str] {
+                Self::mapped_columns()
+            }
+
+            #accepts_column
+        }
+
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Column names read by the derived `FromRow` impl, in field order.
+            ///
+            /// Skipped and flattened fields are not included.
+            pub fn mapped_columns() -> &'static [&'static str] {
+                &[#columns]
+            }
+
+            /// Pairs each column of [`Self::mapped_columns`] with its 1-based ordinal,
+            /// to build `column = $n` fragments that stay in sync with the struct.
+            pub fn column_bindings() -> &'static [(&'static str, usize)] {
+                &[#bindings]
+            }
+
+            /// Names of the fields marked `#[column(skip)]`, in field order.
+            ///
+            /// These don't read a column, so they never appear in [`Self::mapped_columns`].
+            pub fn skipped_fields() -> &'static [&'static str] {
+                &[#skipped]
             }
         }
     });
-    tokens.into()
+    tokens.extend(select_sql(input, &generics, fields, &attrs, &container));
+    tokens.extend(order_by::expand(input, &generics, fields, &attrs, &container));
+    tokens.extend(group_by::expand(input, &generics, fields, &attrs, &container)?);
+    tokens.extend(field_count(input, &attrs));
+    tokens.extend(query_macro::columns_const(input, &generics, fields, &attrs, &container));
+    tokens.extend(has_many::group_and_map(input, &generics, fields, &attrs, &container, false)?);
+    tokens.extend(all_skipped);
+    #[cfg(feature = "debug-mapping")]
+    tokens.extend(debug::expand(input, fields, &attrs, &container));
+    Ok(tokens)
 }
 
 /// Implements the `TryFrom<&Row>` trait for a struct
 #[proc_macro_derive(TryFromRow, attributes(column))]
 pub fn try_from_row(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
-    let name = input.ident;
-    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    expand_try_from_row(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
 
-    let has_attr = Cell::new(false);
+fn expand_try_from_row(input: &DeriveInput) -> Result<TokenStream> {
+    let name = &input.ident;
+    let fields = struct_fields(input, "TryFromRow")?;
+    let container = ContainerAttr::new(&input.attrs, fields)?;
+    let attrs = column_attrs(fields)?;
+    check_skipped(fields, &attrs)?;
+    check_nth(fields, &attrs, &container)?;
+    check_row_reads(fields, &attrs, &container)?;
+    check_name_fn(fields, &attrs, &container)?;
+    if let Some(mut tokens) = flatten_owned::expand(input, fields, &attrs, &container, true)? {
+        tokens.extend(field_count(input, &attrs));
+        return Ok(tokens);
+    }
+    if let Some(mut tokens) = json_column::expand(input, &container, true)? {
+        tokens.extend(field_count(input, &attrs));
+        return Ok(tokens);
+    }
+    let generics = flatten_bounds(&input.generics, fields, &attrs, |ty| parse_quote_spanned!(ty.span()=> #ty: ::tokio_postgres_utils::FromRow));
+    let generics = decode_bounds(&generics, fields, &attrs);
+    let (impl_generics, ty_generics, where_clause) = &generics.split_for_impl();
+    let all_skipped = all_skipped(name, &attrs)?;
 
-    let body = quote(|tokens| match input.data {
-        Data::Struct(data) => match &data.fields {
+    // Fields whose decode can fail with other errors than `tokio_postgres::Error` need a boxed error type.
+    let boxed = container.strict_flatten || matches!(fields, Fields::Named(_)) && attrs.iter().any(|attr| attr.kind.boxes_error());
+    // With `error-context`, the errors of fields reading a single column are wrapped in a `ColumnError` naming it,
+    // wherever the error is already boxed, so the feature doesn't change the error type of `TryFrom<&Row>`.
+    let context = cfg!(feature = "error-context") && !container.collect_errors && matches!(fields, Fields::Named(_));
+
+    if container.collect_errors && !matches!(fields, Fields::Named(_)) {
+        return Err(Error::new_spanned(name, "`collect_errors` requires a struct with named fields"));
+    }
+
+    // With `plan`, columns are read at the positions resolved by `FromRowPlan::column_plan`,
+    // with `access`, from any `RowAccess` and flattened fields through `FromRowAccess`,
+    // with `ctx`, `name_fn` fields from the columns their function names for the `ctx` param,
+    // and with `context`, field errors are wrapped in a `ColumnError`.
+    let body_with = |plan: bool, access: bool, ctx: bool, context: bool| {
+        let (attrs, container) = (&attrs, &container);
+        quote(move |tokens| match fields {
             Fields::Named(fields) => {
+                let mut decode = TokenStream::new();
+                let mut vars = TokenStream::new();
+                let mut values = TokenStream::new();
                 let body = quote(|tokens| {
-                    for field in &fields.named {
-                        if let Some(name) = &field.ident {
+                    let mut planned = 0;
+                    for (i, (field, attr)) in fields.named.iter().zip(attrs).enumerate() {
+                        let name = field.ident.as_ref().unwrap();
+                        let slot = planned;
+                        if column_name(name, attr, container).is_some() {
+                            planned += 1;
+                        }
+                        let index = || {
+                            if let (true, Some(name_fn)) = (ctx, &attr.name_fn) {
+                                let mut tokens = TokenStream::new();
+                                quote!(tokens, { &*(#name_fn)(ctx) });
+                                tokens
+                            } else if plan && attr.ordinal.is_none() {
+                                plan_index(slot)
+                            } else {
+                                column_index(name, attr, container, i)
+                            }
+                        };
+                        if let ColumnKind::Skip = attr.kind {
+                            if !container.base_default && container.builder.is_none() {
+                                quote!(tokens, { #name: });
+                                skip_value(tokens, &field.ty);
+                            }
+                            continue;
+                        }
+                        if container.builder.is_none() {
                             quote!(tokens, { #name: });
-                            match column_attr(&field.attrs) {
-                                ColumnAttr::Flatten => {
-                                    has_attr.set(true);
-                                    quote!(tokens, {
-                                        ::std::convert::TryFrom::try_from(r)?,
-                                    });
-                                }
-                                ColumnAttr::Rename(rename) => {
-                                    quote!(tokens, {
-                                        r.try_get(#rename)?,
-                                    });
-                                },
-                                ColumnAttr::None => {
-                                    let raw_str = name.to_string();
-                                    quote!(tokens, {
-                                        r.try_get(#raw_str)?,
-                                    });
-                                }
-                                ColumnAttr::Skip => {
-                                    quote!(tokens, {
-                                        ::std::default::Default::default(),
-                                    });
-                                }
+                        }
+                        let value = quote(|tokens| match attr.kind {
+                            ColumnKind::Flatten if access => {
+                                quote!(tokens, {
+                                    ::tokio_postgres_utils::FromRowAccess::from_row_access(r)?
+                                });
+                            }
+                            ColumnKind::Flatten => {
+                                quote!(tokens, {
+                                    ::tokio_postgres_utils::__private::Flatten::flatten(r)?
+                                });
+                            }
+                            ColumnKind::HasMany { ref child, .. } => {
+                                quote!(tokens, {
+                                    {
+                                        let child: ::std::option::Option<#child> = ::tokio_postgres_utils::__private::Flatten::flatten(r)?;
+                                        child.into_iter().collect()
+                                    }
+                                });
+                            }
+                            ColumnKind::FlattenRest => {
+                                let consumed = consumed_columns(fields, attrs, container);
+                                quote!(tokens, {
+                                    r.columns()
+                                        .iter()
+                                        .enumerate()
+                                        .filter(|(_, c)| !#consumed.contains(&c.name()))
+                                        .map(|(i, c)| ::std::result::Result::Ok((c.name().to_owned(), r.try_get(i)?)))
+                                        .collect::<::std::result::Result<_, tokio_postgres::Error>>()?
+                                });
+                            }
+                            ColumnKind::ByteaBase64 => {
+                                let column = index();
+                                let ty = &field.ty;
+                                quote!(tokens, {
+                                    <#ty as ::tokio_postgres_utils::__private::Base64Field>::from_column(r.try_get(#column)?)
+                                });
+                            }
+                            ColumnKind::InfallibleGet => {
+                                let column = index();
+                                quote!(tokens, {
+                                    r.get(#column)
+                                });
                             }
+                            ColumnKind::Decimal => {
+                                let column = index();
+                                let ty = &field.ty;
+                                quote!(tokens, {
+                                    <#ty as ::tokio_postgres_utils::__private::DecimalField>::from_column(r.try_get(#column)?)
+                                });
+                            }
+                            ColumnKind::Inet => {
+                                let column = index();
+                                let ty = &field.ty;
+                                quote!(tokens, {
+                                    <#ty as ::tokio_postgres_utils::__private::InetField>::from_column(r.try_get(#column)?)
+                                });
+                            }
+                            ColumnKind::Uuid => {
+                                let column = index();
+                                let ty = &field.ty;
+                                quote!(tokens, {
+                                    <#ty as ::tokio_postgres_utils::__private::UuidField>::from_column(r.try_get(#column)?)
+                                });
+                            }
+                            ColumnKind::FromStr => {
+                                let column = index();
+                                let ty = &field.ty;
+                                quote!(tokens, {
+                                    {
+                                        let text: &str = r.try_get(#column)?;
+                                        <#ty as ::std::str::FromStr>::from_str(text)?
+                                    }
+                                });
+                            }
+                            ColumnKind::Json => {
+                                let column = index();
+                                let ty = &field.ty;
+                                let field = Literal::string(&name.unraw().to_string());
+                                quote!(tokens, {
+                                    <#ty as ::tokio_postgres_utils::__private::JsonField>::from_json(#field, r.try_get(#column)?)?
+                                });
+                            }
+                            ColumnKind::TryTypes(ref types) => {
+                                let value = try_types(&field.ty, types, &index());
+                                quote!(tokens, { #value? });
+                            }
+                            ColumnKind::With(ref with) => {
+                                let value = try_get(attr, &index());
+                                quote!(tokens, {
+                                    (#with)(#value?)
+                                });
+                            }
+                            ColumnKind::FromRow(ref from_row) => {
+                                quote!(tokens, {
+                                    (#from_row)(r)?
+                                });
+                            }
+                            ColumnKind::ArrayOfComposite => {
+                                let column = index();
+                                quote!(tokens, {
+                                    {
+                                        let values: ::std::vec::Vec<::tokio_postgres_utils::Composite<_>> = r.try_get(#column)?;
+                                        values.into_iter().map(|c| c.0).collect()
+                                    }
+                                });
+                            }
+                            _ => {
+                                let value = try_get(attr, &index());
+                                quote!(tokens, {
+                                    #value?
+                                });
+                            }
+                        });
+                        // The blocks below keep clippy from flagging `Ok(value?)` where `value` is already a `BoxError` result.
+                        if container.collect_errors {
+                            let var = &Ident::new(&format!("__{}", name.unraw()), name.span());
+                            let label = match column_name(name, attr, container) {
+                                Some(column) => mapped_column(name, column, container),
+                                None => TokenStream::from(TokenTree::Literal(Literal::string(&name.unraw().to_string()))),
+                            };
+                            quote!(decode, {
+                                let #var = ::tokio_postgres_utils::__private::collect(&mut __errors, #label, (|| ::std::result::Result::Ok({ #value }))());
+                            });
+                            quote!(vars, { ::std::option::Option::Some(#var), });
+                            quote!(values, { #var, });
+                            quote!(tokens, { #var, });
+                        } else if let Some(column) = column_name(name, attr, container).filter(|_| context) {
+                            let column = mapped_column(name, column, container);
+                            quote!(tokens, {
+                                ::tokio_postgres_utils::__private::with_context(#column, (|| ::std::result::Result::Ok({ #value }))())?,
+                            });
+                        } else {
+                            quote!(tokens, { #value, });
                         }
                     }
+                    base_default(tokens, container);
                 });
-                quote!(tokens, {
-                    { #body }
+                let overlap = quote(|tokens| {
+                    if !container.strict_flatten {
+                        return;
+                    }
+                    let reads = strict_flatten_reads(fields, attrs, container);
+                    if container.collect_errors {
+                        quote!(tokens, {
+                            if let ::std::result::Result::Err((column, err)) = ::tokio_postgres_utils::__private::check_overlap(#reads) {
+                                let mut __errors = ::tokio_postgres_utils::MultiDecodeError::default();
+                                ::tokio_postgres_utils::__private::collect::<()>(&mut __errors, column, ::std::result::Result::Err(err));
+                                return ::std::result::Result::Err(::std::convert::From::from(__errors));
+                            }
+                        });
+                    } else {
+                        quote!(tokens, {
+                            ::tokio_postgres_utils::__private::check_overlap(#reads).map_err(|(_, err)| err)?;
+                        });
+                    }
                 });
+                if container.collect_errors {
+                    let mut literal = TokenStream::new();
+                    construct(&mut literal, container, true, body);
+                    quote!(tokens, {
+                        {
+                            #overlap
+                            let mut __errors = ::tokio_postgres_utils::MultiDecodeError::default();
+                            #decode
+                            #[allow(unreachable_patterns)]
+                            match (#values) {
+                                (#vars) => ::std::result::Result::Ok(#literal),
+                                _ => ::std::result::Result::Err(::std::convert::From::from(__errors)),
+                            }
+                        }
+                    });
+                } else {
+                    let mut literal = TokenStream::new();
+                    construct(&mut literal, container, true, body);
+                    quote!(tokens, {
+                        {
+                            #overlap
+                            ::std::result::Result::Ok(#literal)
+                        }
+                    });
+                }
             }
             Fields::Unnamed(fields) => {
                 let body = quote(|tokens| {
+                    if let Some(column) = transparent_column(input, attrs) {
+                        quote!(tokens, { r.try_get(#column)? });
+                        return;
+                    }
                     for (i, _) in fields.unnamed.iter().enumerate() {
                         let idx = Index::from(i);
                         quote!(tokens, {
@@ -157,67 +618,809 @@ pub fn try_from_row(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
                         });
                     }
                 });
+                let mut literal = TokenStream::new();
+                construct(&mut literal, container, false, body);
                 quote!(tokens, {
-                    (#body)
+                    ::std::result::Result::Ok(#literal)
                 });
             }
-            Fields::Unit => {}
-        },
-        Data::Enum(_) | Data::Union(_) => unimplemented!(),
-    });
+            Fields::Unit => {
+                quote!(tokens, {
+                    ::std::result::Result::Ok(Self)
+                });
+            }
+        })
+    };
+    let body = body_with(false, false, false, context && boxed);
 
     let err_ty = quote(|t| {
-        if has_attr.get() {
+        if container.collect_errors {
+            quote!(t, { ::tokio_postgres_utils::MultiDecodeError });
+        } else if boxed {
             quote!(t, { ::std::boxed::Box<dyn ::std::error::Error + ::std::marker::Send + ::std::marker::Sync> });
         } else {
             quote!(t, { tokio_postgres::Error });
         }
     });
+    let err_ty = {
+        let mut tokens = TokenStream::new();
+        quote!(tokens, { #err_ty });
+        tokens
+    };
+
+    let mut body_tokens = TokenStream::new();
+    quote!(body_tokens, { #body });
+
+    let columns = quote(|tokens| match (&container.from_column, fields) {
+        (Some(column), _) => {
+            quote!(tokens, { &[#column] });
+        }
+        (None, Fields::Named(fields)) => tokens.extend(consumed_columns(fields, &attrs, &container)),
+        (None, _) => {
+            let column = transparent_column(input, &attrs);
+            quote!(tokens, { &[#column] });
+        }
+    });
 
     let mut tokens = TokenStream::new();
+    // `from_column` reads all fields from one composite column, through the `composite` impl.
+    let composite = container.composite || container.from_column.is_some();
+    let attr_name = if container.composite { "composite" } else { "from_column" };
+    if composite && container.lenient {
+        return Err(Error::new_spanned(name, format!("`lenient` can't be combined with `{attr_name}`")));
+    }
+    if composite && container.strict_flatten {
+        return Err(Error::new_spanned(name, format!("`strict_flatten` can't be combined with `{attr_name}`")));
+    }
+    if composite && container.case_insensitive {
+        return Err(Error::new_spanned(name, format!("`case_insensitive` can't be combined with `{attr_name}`")));
+    }
+    if composite && attrs.iter().any(|attr| matches!(attr.kind, ColumnKind::FlattenRest)) {
+        return Err(Error::new_spanned(name, format!("`flatten_rest` can't be combined with `{attr_name}`")));
+    }
+    let has_many = attrs.iter().any(|attr| matches!(attr.kind, ColumnKind::HasMany { .. }));
+    if composite && has_many {
+        return Err(Error::new_spanned(name, format!("`has_many` can't be combined with `{attr_name}`")));
+    }
+    if composite {
+        let mut generics = flatten_bounds(&input.generics, fields, &attrs, |ty| {
+            parse_quote_spanned!(ty.span()=> ::tokio_postgres_utils::CompositeRow<'__r>: ::tokio_postgres_utils::__private::Flatten<#ty>)
+        });
+        generics.params.insert(0, parse_quote!('__r));
+        let (impl_generics, _, where_clause) = generics.split_for_impl();
+        let body = body_with(false, false, false, context);
+        quote!(tokens, {
+            impl #impl_generics ::std::convert::TryFrom<&::tokio_postgres_utils::CompositeRow<'__r>> for #name #ty_generics #where_clause {
+                #[inline]
+                fn try_from(r: &::tokio_postgres_utils::CompositeRow<'__r>) -> ::std::result::Result<Self, Self::Error> {
+                    #body
+                }
+                type Error = ::std::boxed::Box<dyn ::std::error::Error + ::std::marker::Send + ::std::marker::Sync>;
+            }
+        });
+    }
+    if container.binary_copy {
+        tokens.extend(binary_copy::expand(input, fields, &attrs)?);
+    }
+    if container.row_access {
+        // A `RowAccess` only reads columns by name, it can't list or search them.
+        let flatten_rest = attrs.iter().any(|attr| matches!(attr.kind, ColumnKind::FlattenRest));
+        let conflicts = [
+            (composite, attr_name),
+            (container.lenient, "lenient"),
+            (container.case_insensitive, "case_insensitive"),
+            (flatten_rest, "flatten_rest"),
+            (has_many, "has_many"),
+        ];
+        if let Some((_, attr_name)) = conflicts.iter().find(|(on, _)| *on) {
+            return Err(Error::new_spanned(name, format!("`{attr_name}` can't be combined with `row_access`")));
+        }
+        let generics = flatten_bounds(&input.generics, fields, &attrs, |ty| parse_quote_spanned!(ty.span()=> #ty: ::tokio_postgres_utils::FromRowAccess));
+        let generics = decode_bounds(&generics, fields, &attrs);
+        let (impl_generics, _, where_clause) = generics.split_for_impl();
+        let access_body = body_with(false, true, false, context);
+        let mut body_tokens = TokenStream::new();
+        quote!(body_tokens, { #access_body });
+        let mut err_ty = TokenStream::new();
+        quote!(err_ty, { ::tokio_postgres_utils::BoxError });
+        let access_body = trace::wrap(name, &container, Some(err_ty), body_tokens);
+        quote!(tokens, {
+            impl #impl_generics ::tokio_postgres_utils::FromRowAccess for #name #ty_generics #where_clause {
+                fn from_row_access<__R: ::tokio_postgres_utils::RowAccess + ?::std::marker::Sized>(
+                    r: &__R,
+                ) -> ::std::result::Result<Self, ::tokio_postgres_utils::BoxError> {
+                    #access_body
+                }
+            }
+        });
+    }
+    if container.from_column.is_none() {
+        let names = quote(|tokens| {
+            for (i, (field, attr)) in fields.iter().zip(&attrs).enumerate() {
+                let Some(name) = &field.ident else {
+                    continue;
+                };
+                let Some(column) = column_name(name, attr, &container) else {
+                    continue;
+                };
+                let column = mapped_column(name, column, &container);
+                let nth = attr.nth.unwrap_or(0);
+                quote!(tokens, { (#column, #nth, #i), });
+            }
+        });
+        let flag = |tokens: &mut TokenStream, on: bool| {
+            if on {
+                quote!(tokens, { true });
+            } else {
+                quote!(tokens, { false });
+            }
+        };
+        let case_insensitive = quote(|tokens| flag(tokens, container.lenient || container.case_insensitive));
+        let fallback = quote(|tokens| flag(tokens, container.lenient));
+        let plan_body = body_with(true, false, false, context);
+        quote!(tokens, {
+            impl #impl_generics ::tokio_postgres_utils::FromRowPlan for #name #ty_generics #where_clause {
+                fn column_plan_by_name(columns: &[&str]) -> ::std::result::Result<::tokio_postgres_utils::ColumnPlan, ::tokio_postgres_utils::BoxError> {
+                    ::tokio_postgres_utils::__private::column_plan(columns, &[#names], #case_insensitive, #fallback)
+                }
+
+                fn from_row_with_plan(
+                    r: &tokio_postgres::Row,
+                    __plan: &::tokio_postgres_utils::ColumnPlan,
+                ) -> ::std::result::Result<Self, ::tokio_postgres_utils::BoxError> {
+                    #plan_body
+                }
+            }
+        });
+    }
+    let ctx_err_ty = err_ty.clone();
+    let (row_body, err_ty) = match &container.from_column {
+        Some(column) => {
+            let mut body = TokenStream::new();
+            quote!(body, {
+                r.try_get::<_, ::tokio_postgres_utils::Composite<Self>>(#column)
+                    .map(|c| c.0)
+                    .map_err(::std::convert::Into::into)
+            });
+            let mut err_ty = TokenStream::new();
+            quote!(err_ty, { ::std::boxed::Box<dyn ::std::error::Error + ::std::marker::Send + ::std::marker::Sync> });
+            (body, err_ty)
+        }
+        None => (body_tokens, err_ty.clone()),
+    };
+    let row_body = trace::wrap(name, &container, Some(err_ty.clone()), row_body);
+    // `from_row` returns a `BoxError`, so it can add the `error-context` that `try_from` leaves out.
+    let from_row_body = if context && !boxed && container.from_column.is_none() {
+        let body = body_with(false, false, false, true);
+        let mut body_tokens = TokenStream::new();
+        quote!(body_tokens, { #body });
+        let mut err_ty = TokenStream::new();
+        quote!(err_ty, { ::tokio_postgres_utils::BoxError });
+        trace::wrap(name, &container, Some(err_ty), body_tokens)
+    } else {
+        let mut body = TokenStream::new();
+        quote!(body, { ::std::convert::TryFrom::try_from(r).map_err(::std::convert::Into::into) });
+        body
+    };
+    let accepts_column = accepts_column(fields, &attrs, &container);
     quote!(tokens, {
         impl #impl_generics ::std::convert::TryFrom<&tokio_postgres::Row> for #name #ty_generics #where_clause {
             #[inline]
             fn try_from(r: &tokio_postgres::Row) -> ::std::result::Result<Self, Self::Error> {
-                Ok(Self #body)
+                #row_body
             }
             type Error = #err_ty;
         }
+
+        impl #impl_generics ::tokio_postgres_utils::FromRow for #name #ty_generics #where_clause {
+            #[inline]
+            fn from_row(r: &tokio_postgres::Row) -> ::std::result::Result<Self, ::tokio_postgres_utils::BoxError> {
+                #from_row_body
+            }
+
+            #[inline]
+            fn columns() -> &'static [&'static str] {
+                #columns
+            }
+
+            #accepts_column
+        }
     });
-    tokens.into()
+    tokens.extend(select_sql(input, &generics, fields, &attrs, &container));
+    tokens.extend(order_by::expand(input, &generics, fields, &attrs, &container));
+    tokens.extend(group_by::expand(input, &generics, fields, &attrs, &container)?);
+    tokens.extend(field_count(input, &attrs));
+    tokens.extend(query_macro::columns_const(input, &generics, fields, &attrs, &container));
+    tokens.extend(has_many::group_and_map(input, &generics, fields, &attrs, &container, true)?);
+    tokens.extend(key_opt::try_from_row_opt(input, &generics, fields, &attrs, &container));
+    if let Some(ctx) = &container.ctx {
+        let ctx_body = body_with(false, false, true, context && boxed);
+        let mut body_tokens = TokenStream::new();
+        quote!(body_tokens, { #ctx_body });
+        let ctx_body = trace::wrap(name, &container, Some(ctx_err_ty.clone()), body_tokens);
+        quote!(tokens, {
+            impl #impl_generics #name #ty_generics #where_clause {
+                /// Like `try_from`, reading each `name_fn` field from the column its function names for `ctx`.
+                pub fn try_from_row_with_ctx(
+                    r: &tokio_postgres::Row,
+                    ctx: &#ctx,
+                ) -> ::std::result::Result<Self, #ctx_err_ty> {
+                    #ctx_body
+                }
+            }
+        });
+    }
+    tokens.extend(all_skipped);
+    #[cfg(feature = "debug-mapping")]
+    tokens.extend(debug::expand(input, fields, &attrs, &container));
+    Ok(tokens)
 }
 
+/// Generates a `partial_update` method building a PATCH-style `UPDATE` statement.
+///
+/// `Option` fields are only written when they are `Some`, other fields are always written,
+/// and the field marked `#[column(key)]` is used in the `WHERE` clause.
+///
+/// ## Example
+///
+/// ```rust, ignore
+/// #[derive(PartialUpdate)]
+/// struct UserPatch {
+///     #[column(key)]
+///     id: i32,
+///     name: Option<String>,
+///     email: Option<String>,
+/// }
+///
+/// let (sql, params) = patch.partial_update("users");
+/// client.execute(&sql, &params).await?;
+/// ```
+#[proc_macro_derive(PartialUpdate, attributes(column))]
+pub fn partial_update(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    partial_update::expand(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
 
+/// Builds `Self` from the field values in `body`, by calling the `builder` path with them when set.
+fn construct(tokens: &mut TokenStream, container: &ContainerAttr, named: bool, body: impl IntoTokens) {
+    match &container.builder {
+        Some(builder) => {
+            quote!(tokens, { #builder(#body) });
+        }
+        None if named => {
+            quote!(tokens, { Self { #body } });
+        }
+        None => {
+            quote!(tokens, { Self(#body) });
+        }
+    }
+}
 
-enum ColumnAttr {
-    Skip,
-    Flatten,
-    None,
-    Rename(Literal),
+/// With `base_default`, fills the fields left out of a struct literal from `Self::default()`.
+fn base_default(tokens: &mut TokenStream, container: &ContainerAttr) {
+    if container.base_default {
+        quote!(tokens, { ..::std::default::Default::default() });
+    }
 }
 
-fn column_attr(attrs: &[Attribute]) -> ColumnAttr {
-    attrs
-        .iter()
-        .find_map(|attr| {
-            if let Meta::List(MetaList { path, tokens, .. }) = &attr.meta {
-                if path.segments.first()?.ident == "column" {
-                    let mut tokens = tokens.clone().into_iter();
-                    match tokens.next()?.to_string().as_str() {
-                        "skip" => return Some(ColumnAttr::Skip),
-                        "flatten" => return Some(ColumnAttr::Flatten),
-                        "rename" => {
-                            if matches!(tokens.next()?, TokenTree::Punct(p) if p.as_char() == '=') {
-                                if let TokenTree::Literal(lit) = tokens.next()? {
-                                    return Some(ColumnAttr::Rename(lit));
-                                }
-                            }
-                        }
-                        _ => {}
-                    }
+/// The value of a skipped field, `None` for `Option` fields, an empty cell for `OnceCell` fields and `Default::default()` otherwise.
+fn skip_value(tokens: &mut TokenStream, ty: &Type) {
+    if is_unit(ty) {
+        quote!(tokens, { (), });
+    } else if is_option(ty) {
+        quote!(tokens, { ::std::option::Option::None, });
+    } else if is_once_cell(ty) {
+        quote!(tokens, { <#ty>::new(), });
+    } else {
+        // Spanned at the field type, so a type without `Default` is reported at the skipped field.
+        let default: Expr = parse_quote_spanned!(ty.span()=> <#ty as ::std::default::Default>::default());
+        quote!(tokens, { #default, });
+    }
+}
+
+/// The columns each field of a `strict_flatten` struct reads, as `&[(field, columns, overrides)]`.
+fn strict_flatten_reads(fields: &FieldsNamed, attrs: &[ColumnAttr], container: &ContainerAttr) -> TokenStream {
+    let reads = quote(|tokens| {
+        for (field, attr) in fields.named.iter().zip(attrs) {
+            let name = field.ident.as_ref().unwrap();
+            let label = Literal::string(&name.unraw().to_string());
+            let ty = &field.ty;
+            if let ColumnKind::Flatten = attr.kind {
+                let overrides = attr.overrides;
+                quote!(tokens, { (#label, <#ty as ::tokio_postgres_utils::FromRow>::columns(), #overrides), });
+            } else if let Some(column) = column_name(name, attr, container) {
+                quote!(tokens, { (#label, &[#column], false), });
+            }
+        }
+    });
+    let mut tokens = TokenStream::new();
+    quote!(tokens, {
+        &[#reads]
+    });
+    tokens
+}
+
+/// Adds a `bound` on the type of each flattened field, e.g. `inner: T` or `repo: Repo<User>`.
+///
+/// A concrete type needs no bound, but stating it reports a type that can't be flattened at its field.
+fn flatten_bounds(generics: &Generics, fields: &Fields, attrs: &[ColumnAttr], bound: impl Fn(&Type) -> WherePredicate) -> Generics {
+    let mut generics = generics.clone();
+    for (field, attr) in fields.iter().zip(attrs) {
+        if matches!(attr.kind, ColumnKind::Flatten) {
+            generics.make_where_clause().predicates.push(bound(&field.ty));
+        }
+    }
+    generics
+}
+
+/// Adds a `FromSql` bound on the type of each field decoded as is, spanned at the field,
+/// so a type that can't be decoded is reported there instead of at the derive.
+fn decode_bounds(generics: &Generics, fields: &Fields, attrs: &[ColumnAttr]) -> Generics {
+    let mut generics = generics.clone();
+    for (field, attr) in fields.iter().zip(attrs) {
+        let decoded = field.ident.is_none()
+            || matches!(attr.kind, ColumnKind::None | ColumnKind::InfallibleGet);
+        if decoded {
+            let ty = &field.ty;
+            generics
+                .make_where_clause()
+                .predicates
+                .push(parse_quote_spanned!(ty.span()=> #ty: tokio_postgres::types::FromSqlOwned));
+        }
+    }
+    generics
+}
+
+/// Rejects `nth` on structs whose columns are not found by searching the row's columns.
+fn check_nth(fields: &Fields, attrs: &[ColumnAttr], container: &ContainerAttr) -> Result<()> {
+    let conflicts = [
+        (container.lenient, "lenient"),
+        (container.case_insensitive, "case_insensitive"),
+        (container.name_map.is_some(), "name_map"),
+        (container.row_access, "row_access"),
+        (container.composite, "composite"),
+        (container.from_column.is_some(), "from_column"),
+    ];
+    let Some((_, attr_name)) = conflicts.iter().find(|(on, _)| *on) else {
+        return Ok(());
+    };
+    match fields.iter().zip(attrs).find(|(_, attr)| attr.nth.is_some()) {
+        Some((field, _)) => Err(Error::new_spanned(field, format!("`nth` can't be combined with `{attr_name}`"))),
+        None => Ok(()),
+    }
+}
+
+/// Rejects `ordinal` and `from_row` fields on structs that aren't read from a `Row`.
+fn check_row_reads(fields: &Fields, attrs: &[ColumnAttr], container: &ContainerAttr) -> Result<()> {
+    let conflicts = [
+        (container.row_access, "row_access"),
+        (container.composite, "composite"),
+        (container.from_column.is_some(), "from_column"),
+        (container.binary_copy, "binary_copy"),
+        (container.table.is_some(), "table"),
+    ];
+    let Some((_, attr_name)) = conflicts.iter().find(|(on, _)| *on) else {
+        return Ok(());
+    };
+    for (field, attr) in fields.iter().zip(attrs) {
+        let read = match attr.kind {
+            ColumnKind::FromRow(_) => "from_row",
+            _ if attr.ordinal.is_some() => "ordinal",
+            _ => continue,
+        };
+        return Err(Error::new_spanned(field, format!("`{read}` can't be combined with `{attr_name}`")));
+    }
+    Ok(())
+}
+
+/// Rejects `name_fn` fields without the `ctx` type to call them with, and `ctx` on structs that aren't read field by field.
+fn check_name_fn(fields: &Fields, attrs: &[ColumnAttr], container: &ContainerAttr) -> Result<()> {
+    let Some(ctx) = &container.ctx else {
+        return match fields.iter().zip(attrs).find(|(_, attr)| attr.name_fn.is_some()) {
+            Some((field, _)) => Err(Error::new_spanned(
+                field,
+                "`name_fn` requires the type of its context, e.g. `#[column(ctx = Tenant)]`",
+            )),
+            None => Ok(()),
+        };
+    };
+    let conflicts = [
+        (container.composite, "composite"),
+        (container.from_column.is_some(), "from_column"),
+        (container.from_json_column.is_some(), "from_json_column"),
+    ];
+    match conflicts.iter().find(|(on, _)| *on) {
+        Some((_, attr_name)) => Err(Error::new_spanned(ctx, format!("`ctx` can't be combined with `{attr_name}`"))),
+        None => Ok(()),
+    }
+}
+
+/// Rejects skipped fields that can't be constructed without a value, such as `LazyCell`.
+fn check_skipped(fields: &Fields, attrs: &[ColumnAttr]) -> Result<()> {
+    for (field, attr) in fields.iter().zip(attrs) {
+        if matches!(attr.kind, ColumnKind::Skip) && is_lazy(&field.ty) {
+            return Err(Error::new_spanned(
+                &field.ty,
+                "a skipped lazy cell needs its compute function, which `skip` can't provide, use a `OnceCell` and fill it on first access instead",
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// The `&[&str]` of column names read by name, which `flatten_rest` leaves out.
+fn consumed_columns(fields: &FieldsNamed, attrs: &[ColumnAttr], container: &ContainerAttr) -> TokenStream {
+    let columns = quote(|tokens| {
+        for (field, attr) in fields.named.iter().zip(attrs) {
+            if let Some(column) = column_name(field.ident.as_ref().unwrap(), attr, container) {
+                quote!(tokens, { #column, });
+            }
+        }
+    });
+    let mut tokens = TokenStream::new();
+    quote!(tokens, {
+        (&[#columns] as &[&str])
+    });
+    tokens
+}
+
+/// The `FromRow::accepts_column` impl, checking the column type against the field type of every field read by name.
+///
+/// Fields whose column type differs from their own, such as `decimal` or `array_of_composite` ones, can't tell.
+fn accepts_column(fields: &Fields, attrs: &[ColumnAttr], container: &ContainerAttr) -> TokenStream {
+    let arms = quote(|tokens| {
+        if container.from_column.is_some() {
+            return;
+        }
+        for (field, attr) in fields.iter().zip(attrs) {
+            let Some(column) = field.ident.as_ref().and_then(|name| column_name(name, attr, container)) else {
+                continue;
+            };
+            let ty = &field.ty;
+            match attr.kind {
+                ColumnKind::ByteaBase64 => {
+                    quote!(tokens, {
+                        #column => ::std::option::Option::Some(<<#ty as ::tokio_postgres_utils::__private::Base64Field>::Column as tokio_postgres::types::FromSql<'_>>::accepts(ty)),
+                    });
+                }
+                ColumnKind::FromStr => {
+                    quote!(tokens, {
+                        #column => ::std::option::Option::Some(<&str as tokio_postgres::types::FromSql<'_>>::accepts(ty)),
+                    });
+                }
+                ColumnKind::Json => {
+                    quote!(tokens, {
+                        #column => ::std::option::Option::Some(<::tokio_postgres_utils::__private::JsonColumn<'_> as tokio_postgres::types::FromSql<'_>>::accepts(ty)),
+                    });
                 }
+                ColumnKind::TryTypes(ref types) => {
+                    let accepts = quote(|tokens| {
+                        for (i, t) in types.iter().enumerate() {
+                            if i > 0 {
+                                quote!(tokens, { || });
+                            }
+                            quote!(tokens, { <#t as tokio_postgres::types::FromSql<'_>>::accepts(ty) });
+                        }
+                    });
+                    quote!(tokens, {
+                        #column => ::std::option::Option::Some(#accepts),
+                    });
+                }
+                ColumnKind::None | ColumnKind::InfallibleGet => {
+                    quote!(tokens, {
+                        #column => ::std::option::Option::Some(<#ty as tokio_postgres::types::FromSql<'_>>::accepts(ty)),
+                    });
+                }
+                _ => {}
+            }
+        }
+    });
+    let mut tokens = TokenStream::new();
+    quote!(tokens, {
+        #[inline]
+        fn accepts_column(column: &str, ty: &tokio_postgres::types::Type) -> ::std::option::Option<bool> {
+            #[allow(unreachable_patterns)]
+            match column {
+                #arms
+                _ => ::std::option::Option::None,
             }
-            None
+        }
+    });
+    tokens
+}
+
+/// Reads `column` with `try_get`, again up to the `retry` attempts of the field while it fails.
+fn try_get(attr: &ColumnAttr, column: &TokenStream) -> TokenStream {
+    let mut tokens = TokenStream::new();
+    if let Some(attempts) = attr.retry {
+        quote!(tokens, {
+            ::tokio_postgres_utils::__private::retry(#attempts, || r.try_get(#column))
+        });
+    } else {
+        quote!(tokens, { r.try_get(#column) });
+    }
+    tokens
+}
+
+/// Reads a `try_types` field from `column`, trying each of the `types` in order.
+///
+/// Text is parsed into the field type with `FromStr`, any other value converted with `TryFrom`.
+/// Only calls `try_get` on `r`, so it reads from a `Row` as well as a `CompositeRow` or `BinaryCopyOutRow`.
+fn try_types(ty: &Type, types: &[Type], column: &TokenStream) -> TokenStream {
+    let attempts = quote(|tokens| {
+        for t in types {
+            let mut name = TokenStream::new();
+            quote!(name, { #t });
+            let name = Literal::string(&name.to_string().replace(' ', "").replace(',', ", "));
+            let convert = quote(|tokens| {
+                if is_text(t) {
+                    quote!(tokens, { <#ty as ::std::str::FromStr>::from_str(&*value)? });
+                } else {
+                    quote!(tokens, { <#ty as ::std::convert::TryFrom<#t>>::try_from(value)? });
+                }
+            });
+            quote!(tokens, {
+                (#name, &|| {
+                    let value: #t = r.try_get(#column)?;
+                    ::std::result::Result::Ok(#convert)
+                }),
+            });
+        }
+    });
+    let mut tokens = TokenStream::new();
+    quote!(tokens, {
+        ::tokio_postgres_utils::__private::try_types(#column, &[#attempts])
+    });
+    tokens
+}
+
+/// The index a field is read with under a `ColumnPlan`, its `slot` among the fields read by name.
+fn plan_index(slot: usize) -> TokenStream {
+    let slot = Literal::usize_unsuffixed(slot);
+    let mut tokens = TokenStream::new();
+    quote!(tokens, { __plan.indices()[#slot] });
+    tokens
+}
+
+/// The column name a field is read from, looked up in the `name_map` at runtime if there's one.
+fn mapped_column(name: &Ident, column: Literal, container: &ContainerAttr) -> TokenStream {
+    let mut tokens = TokenStream::new();
+    match &container.name_map {
+        Some(map) => {
+            let field = Literal::string(&name.unraw().to_string());
+            quote!(tokens, {
+                ::tokio_postgres_utils::__private::mapped_column(&#map, #field, #column)
+            });
+        }
+        None => {
+            quote!(tokens, { #column });
+        }
+    }
+    tokens
+}
+
+/// The index a field is read with, either its column name or, in `lenient` mode,
+/// the position of the first column matching it case-insensitively.
+/// With `case_insensitive`, the name of the first column matching it case-insensitively, if any.
+/// With `name_map`, the column the map gives for the field, if any.
+/// With `nth`, the position of the `nth` column with the field's column name.
+/// With `ordinal`, the given position, taking precedence over all of the above.
+fn column_index(name: &Ident, attr: &ColumnAttr, container: &ContainerAttr, i: usize) -> TokenStream {
+    let column = column_name(name, attr, container);
+    let mut tokens = TokenStream::new();
+    if let Some(ordinal) = attr.ordinal {
+        tokens.extend([TokenTree::Literal(Literal::usize_suffixed(ordinal))]);
+    } else if let Some(nth) = attr.nth {
+        quote!(tokens, {
+            ::tokio_postgres_utils::__private::nth_column(r.columns(), #column, #nth)
+        });
+    } else if container.name_map.is_some() {
+        tokens.extend(mapped_column(name, column.unwrap(), container));
+    } else if container.lenient {
+        quote!(tokens, {
+            r.columns()
+                .iter()
+                .position(|c| c.name().eq_ignore_ascii_case(#column))
+                .unwrap_or(#i)
+        });
+    } else if container.case_insensitive {
+        let column = &column;
+        quote!(tokens, {
+            r.columns()
+                .iter()
+                .find(|c| c.name().eq_ignore_ascii_case(#column))
+                .map_or(#column, |c| c.name())
+        });
+    } else {
+        quote!(tokens, { #column });
+    }
+    tokens
+}
+
+/// Runs `ClientExt::query_as`, first checking at compile time that the `SELECT` list includes every column the struct reads.
+///
+/// `query_as!(client, User, "SELECT id, name FROM users WHERE id = $1", id)` expands to
+/// `client.query_as::<User>("SELECT ..", (id,))`. Only simple `SELECT` lists are checked, see the README for the rules.
+#[proc_macro]
+pub fn query_as(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as query_macro::QueryAs);
+    query_macro::expand(input).into()
+}
+
+/// Implements the `Insert` trait, writing each field as a column.
+///
+/// Skipped fields are left out, `rename` and `rename_all` are honored.
+#[proc_macro_derive(Insert, attributes(column))]
+pub fn insert(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    insert::expand(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn struct_fields<'a>(input: &'a DeriveInput, derive: &str) -> Result<&'a Fields> {
+    match &input.data {
+        Data::Struct(data) => Ok(&data.fields),
+        Data::Enum(_) | Data::Union(_) => Err(Error::new_spanned(
+            &input.ident,
+            format!("`{derive}` can only be derived for structs"),
+        )),
+    }
+}
+
+/// With `#[column(table)]`, a `select_sql` function listing every column read, including those of flattened fields.
+fn select_sql(input: &DeriveInput, generics: &Generics, fields: &Fields, attrs: &[ColumnAttr], container: &ContainerAttr) -> TokenStream {
+    let mut tokens = TokenStream::new();
+    let Some(table) = &container.table else {
+        return tokens;
+    };
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = &generics.split_for_impl();
+    let columns = quote(|tokens| {
+        if let Some(column) = &container.from_column {
+            quote!(tokens, {
+                columns.push(#column);
+            });
+            return;
+        }
+        for (field, attr) in fields.iter().zip(attrs) {
+            let Some(ident) = &field.ident else {
+                continue;
+            };
+            let ty = &field.ty;
+            match attr.kind {
+                ColumnKind::Skip => {}
+                ColumnKind::Flatten => {
+                    quote!(tokens, {
+                        columns.extend_from_slice(<#ty as ::tokio_postgres_utils::FromRow>::columns());
+                    });
+                }
+                ColumnKind::HasMany { ref child, .. } => {
+                    quote!(tokens, {
+                        columns.extend_from_slice(<#child as ::tokio_postgres_utils::FromRow>::columns());
+                    });
+                }
+                ColumnKind::FlattenRest => {
+                    quote!(tokens, {
+                        columns.push("*");
+                    });
+                }
+                ColumnKind::FromRow(_) => {}
+                _ => {
+                    let column = column_name(ident, attr, container);
+                    quote!(tokens, {
+                        columns.push(#column);
+                    });
+                }
+            }
+        }
+    });
+    // Flattened columns are only known at runtime, so they are all quoted there.
+    let quoted = quote(|tokens| {
+        if container.quote_identifiers {
+            quote!(tokens, {
+                let columns: ::std::vec::Vec<::std::string::String> = columns
+                    .iter()
+                    .map(|c| if *c == "*" { ::std::string::ToString::to_string(c) } else { ::tokio_postgres_utils::__private::quote_ident(c) })
+                    .collect();
+            });
+        }
+    });
+    quote!(tokens, {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Builds a `SELECT` statement reading every mapped column from the `#[column(table)]` table.
+            pub fn select_sql() -> ::std::string::String {
+                let mut columns: ::std::vec::Vec<&str> = ::std::vec::Vec::new();
+                #columns
+                if columns.is_empty() {
+                    columns.push("*");
+                }
+                #quoted
+                ::std::format!("SELECT {} FROM {}", columns.join(", "), #table)
+            }
+        }
+    });
+    tokens
+}
+
+/// The `FIELD_COUNT` of a struct, the number of its fields that aren't skipped.
+fn field_count(input: &DeriveInput, attrs: &[ColumnAttr]) -> TokenStream {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = &input.generics.split_for_impl();
+    let count = Literal::usize_suffixed(attrs.iter().filter(|attr| !matches!(attr.kind, ColumnKind::Skip)).count());
+    let mut tokens = TokenStream::new();
+    quote!(tokens, {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// The number of fields read from the row, those marked `#[column(skip)]` excluded.
+            pub const FIELD_COUNT: usize = #count;
+        }
+    });
+    tokens
+}
+
+/// Warns when every field is skipped, as the row is then never read. An error with the `strict` feature.
+///
+/// Proc macros can't emit warnings on stable, so this references a deprecated item instead.
+fn all_skipped(name: &Ident, attrs: &[ColumnAttr]) -> Result<TokenStream> {
+    let mut tokens = TokenStream::new();
+    if attrs.is_empty() || !attrs.iter().all(|attr| matches!(attr.kind, ColumnKind::Skip)) {
+        return Ok(tokens);
+    }
+    let msg = "every field is `#[column(skip)]`, so the row is never read";
+    if cfg!(feature = "strict") {
+        return Err(Error::new_spanned(name, msg));
+    }
+    let item = &Ident::new("all_fields_skipped", name.span());
+    quote!(tokens, {
+        const _: () = {
+            #[deprecated(note = #msg)]
+            #[allow(non_upper_case_globals)]
+            const #item: () = ();
+            #item
+        };
+    });
+    Ok(tokens)
+}
+
+/// The column a `#[repr(transparent)]` tuple newtype reads by name, set by `rename` on its only field.
+///
+/// Other tuple structs, and newtypes without `rename`, read their fields by position.
+fn transparent_column(input: &DeriveInput, attrs: &[ColumnAttr]) -> Option<Literal> {
+    let transparent = input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("repr"))
+        .any(|attr| attr.parse_args::<Ident>().is_ok_and(|repr| repr == "transparent"));
+    match attrs {
+        [ColumnAttr { rename: Some(Rename::Name(column)), .. }] if transparent => Some(column.clone()),
+        _ => None,
+    }
+}
+
+fn column_attrs(fields: &Fields) -> Result<Vec<ColumnAttr>> {
+    fields
+        .iter()
+        .enumerate()
+        .map(|(index, field)| {
+            let mut attr = ColumnAttr { index, ..column_attr(&field.attrs)? };
+            // A named `()` field has no column to read, so without attributes it's skipped.
+            if field.ident.is_some() && is_unit(&field.ty) && matches!((&attr.kind, &attr.rename), (ColumnKind::None, None)) {
+                attr.kind = ColumnKind::Skip;
+            }
+            Ok(attr)
         })
-        .unwrap_or(ColumnAttr::None)
-}
\ No newline at end of file
+        .collect()
+}
+
+/// Implements `ToSql` for a fieldless enum, writing the variant's label as a Postgres enum value.
+///
+/// Labels default to the variant name, `rename`, `rename_case` and `rename_all` are honored.
+#[proc_macro_derive(ToSqlEnum, attributes(column))]
+pub fn to_sql_enum(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    to_sql_enum::expand(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
@@ -0,0 +1,52 @@
+use crate::attr::{column_name, lit_str, ColumnAttr, ContainerAttr};
+use quote2::{
+    proc_macro2::{Ident, Literal, TokenStream},
+    quote, Quote,
+};
+use syn::{ext::IdentExt, *};
+
+/// A `COL_<FIELD>` key for each column read by name, and an `order_by` function only accepting those keys.
+pub fn expand(input: &DeriveInput, generics: &Generics, fields: &Fields, attrs: &[ColumnAttr], container: &ContainerAttr) -> TokenStream {
+    let mut tokens = TokenStream::new();
+    if !matches!(fields, Fields::Named(_)) || container.from_column.is_some() {
+        return tokens;
+    }
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = &generics.split_for_impl();
+    let keys = quote(|tokens| {
+        for (field, attr) in fields.iter().zip(attrs) {
+            let ident = field.ident.as_ref().unwrap();
+            let Some(column) = column_name(ident, attr, container) else {
+                continue;
+            };
+            let key = Ident::new(&format!("COL_{}", ident.unraw().to_string().to_uppercase()), ident.span());
+            let doc = Literal::string(&format!(
+                " The key of the `{}` column, read by `{}`.",
+                lit_str(&column).unwrap_or_default(),
+                ident.unraw()
+            ));
+            quote!(tokens, {
+                #[doc = #doc]
+                pub const #key: ::tokio_postgres_utils::ColumnKey<Self> = ::tokio_postgres_utils::__private::column_key(#column);
+            });
+        }
+    });
+    let quote_identifiers = quote(|tokens| {
+        if container.quote_identifiers {
+            quote!(tokens, { true });
+        } else {
+            quote!(tokens, { false });
+        }
+    });
+    quote!(tokens, {
+        impl #impl_generics #name #ty_generics #where_clause {
+            #keys
+
+            /// Builds an `ORDER BY` clause from `COL_<FIELD>` keys, or an empty string without keys.
+            pub fn order_by(keys: &[(::tokio_postgres_utils::ColumnKey<Self>, ::tokio_postgres_utils::SortOrder)]) -> ::std::string::String {
+                ::tokio_postgres_utils::__private::order_by(keys, #quote_identifiers)
+            }
+        }
+    });
+    tokens
+}
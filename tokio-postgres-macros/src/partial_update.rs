@@ -0,0 +1,120 @@
+use crate::{
+    attr::{sql_column, ColumnKind, ContainerAttr, WriteRole},
+    column_attrs, struct_fields,
+    ty::is_option,
+};
+use quote2::{
+    proc_macro2::{Literal, TokenStream},
+    quote, Quote,
+};
+use syn::*;
+
+pub fn expand(input: &DeriveInput) -> Result<TokenStream> {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = &input.generics.split_for_impl();
+    let all_fields = struct_fields(input, "PartialUpdate")?;
+    let fields = match all_fields {
+        Fields::Named(fields) => fields,
+        _ => {
+            return Err(Error::new_spanned(
+                name,
+                "`PartialUpdate` can only be derived for structs with named fields",
+            ))
+        }
+    };
+    let container = ContainerAttr::new(&input.attrs, all_fields)?;
+    let attrs = column_attrs(all_fields)?;
+
+    let mut key = None;
+    for (field, attr) in fields.named.iter().zip(&attrs) {
+        if attr.write_with.is_some() {
+            return Err(Error::new_spanned(field, "`write_with` is not supported by `PartialUpdate`"));
+        }
+        if attr.ordinal.is_some() {
+            return Err(Error::new_spanned(field, "`ordinal` is not supported by `PartialUpdate`"));
+        }
+        if let Some(WriteRole::Key) = attr.write {
+            if key.is_some() {
+                return Err(Error::new_spanned(field, "only one `#[column(key)]` field is allowed"));
+            }
+            key = Some((field, attr));
+        }
+        let unsupported = match attr.kind {
+            ColumnKind::Flatten => "flatten",
+            ColumnKind::FlattenOwned => "flatten_owned",
+            ColumnKind::HasMany { .. } => "has_many",
+            ColumnKind::FlattenRest => "flatten_rest",
+            ColumnKind::FromRow(_) => "from_row",
+            // These would be bound as the field is, not in the form their column is read as.
+            ColumnKind::ByteaBase64 => "bytea_base64",
+            ColumnKind::Json => "json",
+            ColumnKind::FromStr => "from_str",
+            ColumnKind::TryTypes(_) => "try_types",
+            ColumnKind::ArrayOfComposite => "array_of_composite",
+            _ => continue,
+        };
+        return Err(Error::new_spanned(
+            field,
+            format!("`{unsupported}` is not supported by `PartialUpdate`"),
+        ));
+    }
+    let Some((key, key_attr)) = key else {
+        return Err(Error::new_spanned(name, "`PartialUpdate` requires a `#[column(key)]` field"));
+    };
+    let key_ident = key.ident.as_ref().unwrap();
+    let key_column = &sql_column(key_ident, key_attr, &container).map(|column| Literal::string(&column));
+
+    let sets = quote(|tokens| {
+        for (field, attr) in fields.named.iter().zip(&attrs) {
+            let ident = field.ident.as_ref().unwrap();
+            if matches!(attr.write, Some(WriteRole::Key)) || matches!(attr.kind, ColumnKind::Skip) {
+                continue;
+            }
+            let column = sql_column(ident, attr, &container).map(|column| Literal::string(&column));
+            if is_option(&field.ty) {
+                quote!(tokens, {
+                    if let ::std::option::Option::Some(value) = &self.#ident {
+                        params.push(value);
+                        sets.push(::std::format!("{} = ${}", #column, params.len()));
+                    }
+                });
+            } else {
+                quote!(tokens, {
+                    params.push(&self.#ident);
+                    sets.push(::std::format!("{} = ${}", #column, params.len()));
+                });
+            }
+        }
+    });
+
+    let mut tokens = TokenStream::new();
+    quote!(tokens, {
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Builds an `UPDATE` statement setting only the present fields, and its parameters.
+            ///
+            /// If there is nothing to update, the statement is a no-op that affects zero rows.
+            pub fn partial_update(&self, table: &str) -> (::std::string::String, ::std::vec::Vec<&(dyn tokio_postgres::types::ToSql + ::std::marker::Sync)>) {
+                let mut sets: ::std::vec::Vec<::std::string::String> = ::std::vec::Vec::new();
+                let mut params: ::std::vec::Vec<&(dyn tokio_postgres::types::ToSql + ::std::marker::Sync)> = ::std::vec::Vec::new();
+                #sets
+                if sets.is_empty() {
+                    return (::std::format!("UPDATE {} SET {} = {} WHERE false", table, #key_column, #key_column), params);
+                }
+                params.push(&self.#key_ident);
+                let sql = ::std::format!("UPDATE {} SET {} WHERE {} = ${}", table, sets.join(", "), #key_column, params.len());
+                (sql, params)
+            }
+        }
+    });
+    if let Some(table) = &container.table {
+        quote!(tokens, {
+            impl #impl_generics #name #ty_generics #where_clause {
+                /// Like `partial_update`, for the `#[column(table)]` table.
+                pub fn partial_update_sql(&self) -> (::std::string::String, ::std::vec::Vec<&(dyn tokio_postgres::types::ToSql + ::std::marker::Sync)>) {
+                    self.partial_update(#table)
+                }
+            }
+        });
+    }
+    Ok(tokens)
+}
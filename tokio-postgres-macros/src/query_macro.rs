@@ -0,0 +1,235 @@
+use crate::attr::{column_name, lit_str, ColumnAttr, ContainerAttr};
+use quote2::{
+    proc_macro2::{Literal, TokenStream},
+    quote, Quote,
+};
+use syn::{
+    ext::IdentExt,
+    parse::{Parse, ParseStream},
+    *,
+};
+
+/// The hidden `__QUERY_COLUMNS` const listing the columns read by name, each with the error `query_as!` reports when it's missing.
+pub fn columns_const(input: &DeriveInput, generics: &Generics, fields: &Fields, attrs: &[ColumnAttr], container: &ContainerAttr) -> TokenStream {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = &generics.split_for_impl();
+    let columns: Vec<String> = match &container.from_column {
+        Some(column) => lit_str(column).into_iter().collect(),
+        None => fields
+            .iter()
+            .zip(attrs)
+            .filter_map(|(field, attr)| column_name(field.ident.as_ref()?, attr, container))
+            .filter_map(|column| lit_str(&column).ok())
+            .collect(),
+    };
+    let entries = quote(|tokens| {
+        for column in &columns {
+            let message = Literal::string(&format!(
+                "column `{column}` read by `{}` is missing from the SELECT list",
+                name.unraw()
+            ));
+            let column = Literal::string(column);
+            quote!(tokens, { (#column, #message), });
+        }
+    });
+    let mut tokens = TokenStream::new();
+    quote!(tokens, {
+        impl #impl_generics #name #ty_generics #where_clause {
+            #[doc(hidden)]
+            pub const __QUERY_COLUMNS: &'static [(&'static str, &'static str)] = &[#entries];
+        }
+    });
+    tokens
+}
+
+/// The input of `query_as!(client, Type, "SELECT ..", params..)`.
+pub struct QueryAs {
+    client: Expr,
+    ty: Type,
+    sql: LitStr,
+    params: Vec<Expr>,
+}
+
+impl Parse for QueryAs {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let client = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let ty = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let sql = input.parse()?;
+        let mut params = Vec::new();
+        while input.parse::<Option<Token![,]>>()?.is_some() && !input.is_empty() {
+            params.push(input.parse()?);
+        }
+        Ok(QueryAs { client, ty, sql, params })
+    }
+}
+
+/// Calls `ClientExt::query_as`, failing to compile when the SELECT list lacks a column the struct reads.
+pub fn expand(input: QueryAs) -> TokenStream {
+    let QueryAs { client, ty, sql, params } = &input;
+    let check = quote(|tokens| {
+        // Lists that can't be parsed, or that select `*`, are left to fail at runtime.
+        let Some(selected) = select_list(&sql.value()) else {
+            return;
+        };
+        let selected = quote(|tokens| {
+            for column in &selected {
+                let column = Literal::string(column);
+                quote!(tokens, { #column, });
+            }
+        });
+        quote!(tokens, {
+            const _: () = ::tokio_postgres_utils::__private::check_select(<#ty>::__QUERY_COLUMNS, &[#selected]);
+        });
+    });
+    let params = quote(|tokens| {
+        for param in params {
+            quote!(tokens, { #param, });
+        }
+    });
+    let mut tokens = TokenStream::new();
+    quote!(tokens, {
+        {
+            #check
+            use ::tokio_postgres_utils::ClientExt as _;
+            (#client).query_as::<#ty>(#sql, (#params))
+        }
+    });
+    tokens
+}
+
+/// The column names of a simple `SELECT` list, or `None` if the statement isn't one or selects `*`.
+///
+/// Items are named like Postgres does: by their alias, else by the column they reference, unquoted names folded to lowercase.
+/// Other expressions without an alias get no name.
+fn select_list(sql: &str) -> Option<Vec<String>> {
+    let words = split_words(sql.trim())?;
+    let (select, rest) = words.split_first()?;
+    if !select.eq_ignore_ascii_case("select") {
+        return None;
+    }
+    let mut rest = rest;
+    if let Some((first, after)) = rest.split_first() {
+        if first.eq_ignore_ascii_case("all") {
+            rest = after;
+        } else if first.eq_ignore_ascii_case("distinct") {
+            rest = after;
+            if rest.first().is_some_and(|w| w.eq_ignore_ascii_case("on")) {
+                rest = rest.get(2..)?;
+            }
+        }
+    }
+    const CLAUSES: &[&str] = &[
+        "from", "where", "group", "having", "window", "order", "limit", "offset", "fetch", "for", "into", "union", "intersect", "except",
+    ];
+    let end = rest
+        .iter()
+        .position(|w| CLAUSES.iter().any(|c| w.eq_ignore_ascii_case(c)))
+        .unwrap_or(rest.len());
+    let mut columns = Vec::new();
+    for item in rest[..end].split(|w| w == ",") {
+        let (last, before) = item.split_last()?;
+        if last == "*" || last.ends_with(".*") {
+            return None;
+        }
+        let name = match before.last() {
+            Some(as_) if as_.eq_ignore_ascii_case("as") => identifier(last),
+            Some(_) if before.len() == 1 => identifier(last),
+            Some(_) => None,
+            None => column_ref(last),
+        };
+        columns.extend(name);
+    }
+    Some(columns)
+}
+
+/// Splits SQL into words at top-level whitespace, with top-level commas as words of their own.
+/// Quoted strings, quoted identifiers and parenthesized groups stay inside their word.
+fn split_words(sql: &str) -> Option<Vec<String>> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let (mut depth, mut quote) = (0usize, None);
+    for c in sql.trim_end_matches(';').chars() {
+        if let Some(q) = quote {
+            word.push(c);
+            if c == q {
+                quote = None;
+            }
+            continue;
+        }
+        match c {
+            '\'' | '"' => {
+                quote = Some(c);
+                word.push(c);
+            }
+            '(' => {
+                depth += 1;
+                word.push(c);
+            }
+            ')' => {
+                depth = depth.checked_sub(1)?;
+                word.push(c);
+            }
+            ',' if depth == 0 => {
+                if !word.is_empty() {
+                    words.push(std::mem::take(&mut word));
+                }
+                words.push(",".to_owned());
+            }
+            c if c.is_whitespace() && depth == 0 => {
+                if !word.is_empty() {
+                    words.push(std::mem::take(&mut word));
+                }
+            }
+            c => word.push(c),
+        }
+    }
+    if quote.is_some() || depth != 0 {
+        return None;
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    Some(words)
+}
+
+/// The name of a column reference such as `id`, `u.id`, `"User"."Name"` or `price::text`.
+fn column_ref(word: &str) -> Option<String> {
+    let word = word.split("::").next()?;
+    let mut name = None;
+    let mut rest = word;
+    while !rest.is_empty() {
+        let part = if let Some(quoted) = rest.strip_prefix('"') {
+            let end = quoted.find('"')?;
+            let part = &rest[..end + 2];
+            rest = &rest[end + 2..];
+            part
+        } else {
+            let end = rest.find('.').unwrap_or(rest.len());
+            let part = &rest[..end];
+            rest = &rest[end..];
+            part
+        };
+        name = Some(identifier(part)?);
+        match rest.strip_prefix('.') {
+            Some(after) if !after.is_empty() => rest = after,
+            Some(_) => return None,
+            None => {}
+        }
+    }
+    name
+}
+
+/// The name of a plain or quoted identifier, unquoted ones folded to lowercase like Postgres does.
+fn identifier(word: &str) -> Option<String> {
+    if let Some(quoted) = word.strip_prefix('"').and_then(|w| w.strip_suffix('"')) {
+        return Some(quoted.replace("\"\"", "\""));
+    }
+    let mut chars = word.chars();
+    let first = chars.next()?;
+    if !(first.is_alphabetic() || first == '_') || !chars.all(|c| c.is_alphanumeric() || c == '_' || c == '$') {
+        return None;
+    }
+    Some(word.to_lowercase())
+}
@@ -0,0 +1,87 @@
+use crate::attr::ContainerAttr;
+use quote2::{
+    proc_macro2::{Ident, Literal, TokenStream},
+    quote, Quote,
+};
+use syn::*;
+
+/// Implements `FromRow` for a fieldless `#[repr(int)]` enum, reading the `repr_enum` column and matching its discriminants.
+pub fn expand(input: &DeriveInput, data: &DataEnum) -> Result<TokenStream> {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = &input.generics.split_for_impl();
+    let container = ContainerAttr::new(&input.attrs, &Fields::Unit)?;
+    let Some(column) = &container.repr_enum else {
+        return Err(Error::new_spanned(
+            name,
+            "`FromRow` can only be derived for structs, or enums with `#[column(repr_enum = \"..\")]`",
+        ));
+    };
+    let repr = &repr(input)?;
+    for variant in &data.variants {
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(Error::new_spanned(variant, "`repr_enum` only supports unit variants"));
+        }
+    }
+
+    let arms = quote(|tokens| {
+        for variant in &data.variants {
+            let ident = &variant.ident;
+            quote!(tokens, {
+                v if v == Self::#ident as #repr => ::std::result::Result::Ok(Self::#ident),
+            });
+        }
+    });
+    let enum_name = Literal::string(&name.to_string());
+
+    let mut tokens = TokenStream::new();
+    quote!(tokens, {
+        impl #impl_generics ::std::convert::TryFrom<&tokio_postgres::Row> for #name #ty_generics #where_clause {
+            fn try_from(r: &tokio_postgres::Row) -> ::std::result::Result<Self, Self::Error> {
+                match r.try_get::<_, #repr>(#column)? {
+                    #arms
+                    v => ::std::result::Result::Err(::std::convert::From::from(::std::format!(
+                        "invalid discriminant {} for `{}` in column `{}`",
+                        v,
+                        #enum_name,
+                        #column
+                    ))),
+                }
+            }
+            type Error = ::tokio_postgres_utils::BoxError;
+        }
+
+        impl #impl_generics ::tokio_postgres_utils::FromRow for #name #ty_generics #where_clause {
+            #[inline]
+            fn from_row(r: &tokio_postgres::Row) -> ::std::result::Result<Self, ::tokio_postgres_utils::BoxError> {
+                ::std::convert::TryFrom::try_from(r)
+            }
+
+            #[inline]
+            fn columns() -> &'static [&'static str] {
+                &[#column]
+            }
+        }
+    });
+    Ok(tokens)
+}
+
+/// The integer type of `#[repr(..)]`, which the column is read as.
+fn repr(input: &DeriveInput) -> Result<Ident> {
+    let mut repr = None;
+    for attr in input.attrs.iter().filter(|attr| attr.path().is_ident("repr")) {
+        attr.parse_nested_meta(|meta| {
+            if let Some(ident) = meta.path.get_ident() {
+                if ["i16", "i32", "i64"].iter().any(|ty| ident == ty) {
+                    repr = Some(ident.clone());
+                }
+            }
+            Ok(())
+        })?;
+    }
+    repr.ok_or_else(|| {
+        Error::new_spanned(
+            &input.ident,
+            "`repr_enum` requires `#[repr(i16)]`, `#[repr(i32)]` or `#[repr(i64)]`, matching the column type",
+        )
+    })
+}
@@ -0,0 +1,77 @@
+use crate::attr::{column_attr, ColumnKind, ContainerAttr, Rename};
+use quote2::{
+    proc_macro2::{Literal, TokenStream},
+    quote, Quote,
+};
+use syn::*;
+
+pub fn expand(input: &DeriveInput) -> Result<TokenStream> {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = &input.generics.split_for_impl();
+    let Data::Enum(data) = &input.data else {
+        return Err(Error::new_spanned(name, "`ToSqlEnum` can only be derived for enums"));
+    };
+    let container = ContainerAttr::new(&input.attrs, &Fields::Unit)?;
+
+    let mut variants = Vec::new();
+    for variant in &data.variants {
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(Error::new_spanned(variant, "`ToSqlEnum` only supports unit variants"));
+        }
+        let attr = column_attr(&variant.attrs)?;
+        if !matches!(attr.kind, ColumnKind::None) {
+            return Err(Error::new_spanned(variant, "only `rename` and `rename_case` are supported on enum variants"));
+        }
+        let rule = match attr.rename {
+            Some(Rename::Name(label)) => {
+                variants.push((&variant.ident, label));
+                continue;
+            }
+            Some(Rename::Case(rule)) => Some(rule),
+            None => container.rename_all,
+        };
+        let label = match rule {
+            Some(rule) => rule.apply_to_variant(&variant.ident.to_string()),
+            None => variant.ident.to_string(),
+        };
+        variants.push((&variant.ident, Literal::string(&label)));
+    }
+
+    let arms = quote(|tokens| {
+        for (ident, label) in &variants {
+            quote!(tokens, { Self::#ident => #label, });
+        }
+    });
+    let labels = quote(|tokens| {
+        for (_, label) in &variants {
+            quote!(tokens, { #label, });
+        }
+    });
+
+    let mut tokens = TokenStream::new();
+    quote!(tokens, {
+        impl #impl_generics tokio_postgres::types::ToSql for #name #ty_generics #where_clause {
+            fn to_sql(
+                &self,
+                ty: &tokio_postgres::types::Type,
+                out: &mut tokio_postgres::types::private::BytesMut,
+            ) -> ::std::result::Result<tokio_postgres::types::IsNull, ::std::boxed::Box<dyn ::std::error::Error + ::std::marker::Sync + ::std::marker::Send>> {
+                let label: &str = match self { #arms };
+                <&str as tokio_postgres::types::ToSql>::to_sql(&label, ty, out)
+            }
+
+            fn accepts(ty: &tokio_postgres::types::Type) -> bool {
+                let labels: &[&str] = &[#labels];
+                match ty.kind() {
+                    tokio_postgres::types::Kind::Enum(variants) => {
+                        labels.iter().all(|label| variants.iter().any(|v| v == label))
+                    }
+                    _ => <&str as tokio_postgres::types::ToSql>::accepts(ty),
+                }
+            }
+
+            tokio_postgres::types::to_sql_checked!();
+        }
+    });
+    Ok(tokens)
+}
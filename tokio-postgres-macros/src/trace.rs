@@ -0,0 +1,33 @@
+use crate::attr::ContainerAttr;
+use quote2::{
+    proc_macro2::{Ident, Literal, TokenStream},
+    quote, Quote,
+};
+use syn::ext::IdentExt;
+
+/// With `trace` and the `tracing` feature, runs a `from`/`try_from` body in a `from_row` trace span
+/// recording the struct name and how long decoding took, in microseconds. Otherwise the body is left as is.
+///
+/// `err` is the error type of a fallible body, `None` for one returning `Self`.
+/// The span is built with the `tracing` crate of the crate deriving the impl.
+pub fn wrap(name: &Ident, container: &ContainerAttr, err: Option<TokenStream>, body: TokenStream) -> TokenStream {
+    if !cfg!(feature = "tracing") || !container.trace {
+        return body;
+    }
+    let type_name = Literal::string(&name.unraw().to_string());
+    let mut ret = TokenStream::new();
+    if let Some(err) = err {
+        quote!(ret, { ::std::result::Result<Self, #err> });
+    } else {
+        quote!(ret, { Self });
+    }
+    let mut tokens = TokenStream::new();
+    quote!(tokens, {
+        let __span = ::tracing::trace_span!("from_row", type_name = #type_name, duration_us = ::tracing::field::Empty).entered();
+        let __start = ::std::time::Instant::now();
+        let __value: #ret = (|| #body)();
+        __span.record("duration_us", __start.elapsed().as_micros() as u64);
+        __value
+    });
+    tokens
+}
@@ -0,0 +1,45 @@
+use syn::*;
+
+/// Returns `true` if the type is spelled as `Option<..>`.
+pub fn is_option(ty: &Type) -> bool {
+    is_generic(ty, &["Option"])
+}
+
+/// Returns `true` if the type is spelled as `OnceCell<..>` or `OnceLock<..>`.
+pub fn is_once_cell(ty: &Type) -> bool {
+    is_generic(ty, &["OnceCell", "OnceLock"])
+}
+
+/// Returns `true` if the type is spelled as `LazyCell<..>`, `LazyLock<..>` or `Lazy<..>`.
+pub fn is_lazy(ty: &Type) -> bool {
+    is_generic(ty, &["LazyCell", "LazyLock", "Lazy"])
+}
+
+/// Returns `true` if the type is the unit type `()`.
+pub fn is_unit(ty: &Type) -> bool {
+    match ty {
+        Type::Tuple(tuple) => tuple.elems.is_empty(),
+        Type::Paren(paren) => is_unit(&paren.elem),
+        _ => false,
+    }
+}
+
+/// Returns `true` if the type is spelled as `String` or `&str`, text that `try_types` parses with `FromStr`.
+pub fn is_text(ty: &Type) -> bool {
+    match ty {
+        Type::Reference(r) => matches!(&*r.elem, Type::Path(p) if p.path.is_ident("str")),
+        Type::Path(TypePath { qself: None, path }) => path.segments.last().is_some_and(|seg| seg.ident == "String" && seg.arguments.is_empty()),
+        Type::Paren(paren) => is_text(&paren.elem),
+        _ => false,
+    }
+}
+
+fn is_generic(ty: &Type, names: &[&str]) -> bool {
+    match ty {
+        Type::Path(TypePath { qself: None, path }) => path
+            .segments
+            .last()
+            .is_some_and(|seg| names.iter().any(|name| seg.ident == name) && matches!(seg.arguments, PathArguments::AngleBracketed(_))),
+        _ => false,
+    }
+}